@@ -0,0 +1,5323 @@
+//! Word search puzzle generator: places horizontal and vertical word lists
+//! into a compact grid using several randomized search algorithms.
+
+use clap::ValueEnum;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::cell::{Cell, RefCell};
+use std::cmp::Reverse;
+use std::collections::{BTreeSet, BinaryHeap, HashMap, HashSet, VecDeque};
+use std::io::IsTerminal;
+use std::time::{Duration, Instant};
+
+mod placement;
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum OutputFormat {
+    /// Grid printed as text
+    Text,
+    /// Comma separated grid rows
+    Csv,
+    /// Printable self-contained HTML page
+    Html,
+    /// Grid, placed words and statistics as JSON
+    Json,
+    /// Printable PDF with the answer key on its own page
+    Pdf,
+    /// Graphviz graph of the words and where they cross
+    Dot,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum PageSize {
+    /// 210 x 297 mm
+    A4,
+    /// 8.5 x 11 in
+    Letter,
+}
+
+impl PageSize {
+    // Width and height in PDF points (1/72 in)
+    fn points(self) -> (f64, f64) {
+        match self {
+            PageSize::A4 => (595.0, 842.0),
+            PageSize::Letter => (612.0, 792.0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum OutputCase {
+    Lower,
+    Upper,
+    /// First letter of each word upper case, the rest lower case
+    Title,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Algorithm {
+    /// Candidate scoring with alternating horizontal/vertical placement
+    Optimized,
+    /// Forces high-value intersections first, then fills in the remaining words
+    IntersectionFirst,
+    /// Random placement with retries
+    Standard,
+    /// Deterministic longest-first placement at the best-scoring position
+    Greedy,
+    /// Depth-first search that always places the word with the fewest open positions
+    /// next (the longer word on ties) and undoes placements at dead ends
+    Backtrack,
+}
+
+impl Algorithm {
+    pub fn name(self) -> &'static str {
+        match self {
+            Algorithm::Optimized => "optimized",
+            Algorithm::IntersectionFirst => "intersection-first",
+            Algorithm::Standard => "standard",
+            Algorithm::Greedy => "greedy",
+            Algorithm::Backtrack => "backtrack",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Orientation {
+    /// As generated
+    Normal,
+    /// Mirror left-right
+    FlipH,
+    /// Mirror top-bottom
+    FlipV,
+    /// Rotate by half a turn
+    Rotate180,
+}
+
+impl Orientation {
+    // This orientation followed by a half turn
+    fn rotated_half_turn(self) -> Orientation {
+        match self {
+            Orientation::Normal => Orientation::Rotate180,
+            Orientation::FlipH => Orientation::FlipV,
+            Orientation::FlipV => Orientation::FlipH,
+            Orientation::Rotate180 => Orientation::Normal,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Shape {
+    Circle,
+    /// Square standing on one corner
+    Diamond,
+    Heart,
+    /// Five-pointed star
+    Star,
+}
+
+impl Shape {
+    /// Cells inside the shape drawn to fill a `width` x `height` grid, indexed
+    /// `[row][col]`. Words may only use cells marked true
+    ///
+    /// ```
+    /// use wordsearch_generator::Shape;
+    ///
+    /// let mask = Shape::Diamond.mask(5, 5);
+    /// assert!(mask[2].iter().all(|&open| open));
+    /// assert_eq!(mask[0], [false, false, true, false, false]);
+    /// ```
+    pub fn mask(self, width: usize, height: usize) -> Vec<Vec<bool>> {
+        (0..height)
+            .map(|r| {
+                (0..width)
+                    .map(|c| {
+                        // Cell center scaled to -1..1, with y increasing upwards
+                        let x = 2.0 * (c as f64 + 0.5) / width as f64 - 1.0;
+                        let y = 1.0 - 2.0 * (r as f64 + 0.5) / height as f64;
+                        self.contains(x, y)
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn contains(self, x: f64, y: f64) -> bool {
+        match self {
+            Shape::Circle => x * x + y * y <= 1.0,
+            Shape::Diamond => x.abs() + y.abs() <= 1.0,
+            Shape::Heart => {
+                // (x² + y² - 1)³ - x²y³ <= 0, stretched to fill the square
+                let (x, y) = (x * 1.14, y * 1.125 + 0.125);
+                (x * x + y * y - 1.0).powi(3) - x * x * y.powi(3) <= 0.0
+            }
+            Shape::Star => {
+                // Point in the ten-sided outline, alternating outer and inner corners
+                let y = y * 0.905 + 0.095;
+                let corners: Vec<(f64, f64)> = (0..10)
+                    .map(|i| {
+                        let angle = std::f64::consts::FRAC_PI_2 + i as f64 * std::f64::consts::PI / 5.0;
+                        let radius = if i % 2 == 0 { 1.0 } else { 0.45 };
+                        (radius * angle.cos(), radius * angle.sin())
+                    })
+                    .collect();
+                let mut inside = false;
+                for (i, &(xi, yi)) in corners.iter().enumerate() {
+                    let (xj, yj) = corners[(i + 9) % 10];
+                    if (yi > y) != (yj > y) && x < (xj - xi) * (y - yi) / (yj - yi) + xi {
+                        inside = !inside;
+                    }
+                }
+                inside
+            }
+        }
+    }
+
+    // Fraction of a square grid the shape covers
+    fn fill_fraction(self) -> f64 {
+        const SIDE: usize = 40;
+        let open = self.mask(SIDE, SIDE).iter().flatten().filter(|&&open| open).count();
+        open as f64 / (SIDE * SIDE) as f64
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ReadingOrder {
+    /// Horizontal words read left-to-right, vertical words top-to-bottom
+    Natural,
+    /// Horizontal words read right-to-left, vertical words bottom-to-top
+    Reversed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Preference {
+    /// Minimize area even at the cost of fewer crossings
+    Compact,
+    /// Maximize crossings even if the grid grows
+    Connected,
+    /// Equal weighting of area, squareness and crossings
+    Balanced,
+}
+
+// Dimension the grid should preferably grow in, as a soft bias rather than a fixed size
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum GrowthBias {
+    Wide,
+    Tall,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PlacementOrder {
+    /// Longest words first
+    Length,
+    /// Words with the most intersection potential first
+    Intersections,
+    /// Longest words first, but the optimized algorithm shuffles its queue every attempt
+    Random,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum QueueInterleave {
+    /// One horizontal word, then one vertical word
+    Alternate,
+    /// All horizontal words first, forming a backbone for the verticals to cross
+    HorizontalsFirst,
+    /// All vertical words first, forming a backbone for the horizontals to cross
+    VerticalsFirst,
+    /// Longest words first regardless of direction
+    ByLength,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum WordBankOrder {
+    /// Alphabetical
+    Alpha,
+    /// Longest words first
+    Length,
+    /// As listed in the input file, horizontal words first
+    Input,
+    /// Random order
+    Shuffled,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, ValueEnum)]
+pub enum AnswerKeyStyle {
+    /// One row per word with its direction and first-letter coordinates
+    #[default]
+    Table,
+    /// Compact "WORD: (row,col) → length" lines
+    Arrows,
+}
+
+/// Corner of the rendered grid that coordinates in the output count from. Rows count
+/// down from a top origin and up from a bottom one; columns count right from a left
+/// origin and left from a right one. Only reported coordinates change, never the
+/// layout itself.
+///
+/// ```
+/// use wordsearch_generator::Origin;
+///
+/// // Cell (1, 3) of a 3-row, 5-column area
+/// let area = (0, 2, 0, 4);
+/// assert_eq!(Origin::TopLeft.locate((1, 3), area), (1, 3));
+/// assert_eq!(Origin::BottomLeft.locate((1, 3), area), (1, 3));
+/// assert_eq!(Origin::TopRight.locate((1, 3), area), (1, 1));
+/// assert_eq!(Origin::BottomRight.locate((0, 0), area), (2, 4));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Default, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Origin {
+    /// (0, 0) is the top-left cell
+    #[default]
+    TopLeft,
+    /// (0, 0) is the top-right cell
+    TopRight,
+    /// (0, 0) is the bottom-left cell
+    BottomLeft,
+    /// (0, 0) is the bottom-right cell
+    BottomRight,
+}
+
+impl Origin {
+    /// `(row, col)` of a cell counted from this corner of the area spanning
+    /// `(first_row, last_row, first_col, last_col)`, in the grid's own indices
+    pub fn locate(self, (row, col): (usize, usize), (first_row, last_row, first_col, last_col): (usize, usize, usize, usize))
+        -> (usize, usize) {
+        let row = match self {
+            Origin::TopLeft | Origin::TopRight => row - first_row,
+            Origin::BottomLeft | Origin::BottomRight => last_row - row,
+        };
+        let col = match self {
+            Origin::TopLeft | Origin::BottomLeft => col - first_col,
+            Origin::TopRight | Origin::BottomRight => last_col - col,
+        };
+        (row, col)
+    }
+
+    // Reorder rows listed top to bottom, each left to right, so the first row and the
+    // first cell of each row are at this corner
+    fn arrange<T>(self, rows: &mut [Vec<T>]) {
+        if matches!(self, Origin::BottomLeft | Origin::BottomRight) {
+            rows.reverse();
+        }
+        if matches!(self, Origin::TopRight | Origin::BottomRight) {
+            rows.iter_mut().for_each(|row| row.reverse());
+        }
+    }
+}
+
+// Which parts of the puzzle an export contains
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Sections {
+    // Title, grid, word bank, instructions and, if requested, the answer key
+    #[default]
+    All,
+    // Just the filled grid
+    Grid,
+    // Just the answer key
+    Solution,
+}
+
+// Presentation settings for rendering a grid - these never affect placement
+#[derive(Debug, Clone, Default)]
+pub struct RenderStyle {
+    pub color: bool,
+    pub output_case: Option<OutputCase>,
+    pub coords: bool,
+    pub empty_char: Option<char>,
+    // Include the answer key in exports that support one
+    pub answer_key: bool,
+    pub answer_key_style: AnswerKeyStyle,
+    pub sections: Sections,
+    // Corner that reported coordinates count from
+    pub origin: Origin,
+}
+
+// A single rendered cell: its visible text plus an optional ANSI color
+#[derive(Debug, Clone)]
+struct LayoutCell {
+    text: String,
+    color: Option<&'static str>,
+    filled: bool,
+}
+
+// Text layout of the used grid region with every cell padded to the same width
+#[derive(Debug, Clone)]
+struct TextLayout {
+    rows: Vec<Vec<LayoutCell>>,
+    row_labels: Vec<String>,
+    col_labels: Vec<String>,
+    cell_width: usize,
+}
+
+// Square cells laid out row by row from the top-left corner of a layout, as both the
+// SVG and the PDF drawings of the grid place them
+#[derive(Debug, Clone, Copy)]
+struct GridGeometry {
+    cell: f64,
+    rows: usize,
+    columns: usize,
+}
+
+impl GridGeometry {
+    fn new(layout: &TextLayout, cell: f64) -> Self {
+        GridGeometry { cell, rows: layout.rows.len(), columns: layout.rows.first().map_or(0, |row| row.len()) }
+    }
+
+    // Largest cell, up to max_cell, at which the whole grid fits in width x height
+    fn fitted(layout: &TextLayout, width: f64, height: f64, max_cell: f64) -> Self {
+        let geometry = Self::new(layout, max_cell);
+        let cell = (width / geometry.columns.max(1) as f64).min(height / geometry.rows.max(1) as f64).min(max_cell);
+        GridGeometry { cell, ..geometry }
+    }
+
+    // (width, height) of the whole grid
+    fn size(&self) -> (f64, f64) {
+        (self.columns as f64 * self.cell, self.rows as f64 * self.cell)
+    }
+
+    // Top-left corner of cell (r, c), measured right and down from the grid's own
+    fn corner(&self, r: usize, c: usize) -> (f64, f64) {
+        (c as f64 * self.cell, r as f64 * self.cell)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct WordLists {
+    #[serde(default)]
+    pub horizontal: Vec<String>,
+    #[serde(default)]
+    pub vertical: Vec<String>,
+    // Optional puzzle metadata shown as a header and footer around the grid
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instructions: Option<String>,
+    // Pairs of words that must never share a cell
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub forbidden_crossings: Vec<(String, String)>,
+    // Pairs of words that must cross each other, one horizontal and one vertical
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub required_crossings: Vec<(String, String)>,
+    // Words placed at fixed positions before the rest are fitted around them
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub pinned: HashMap<String, Pin>,
+    // Theme labels, each listing its words, for coloring the grid and sectioning the
+    // word bank. Placement is unaffected
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub groups: HashMap<String, Vec<String>>,
+    // Words for either direction, shared out between horizontal and vertical before
+    // generating (see split_words)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub words: Vec<String>,
+}
+
+// Words of one group in the word bank. The words of no group have no label
+#[derive(Debug, Clone, PartialEq)]
+pub struct WordBankGroup {
+    pub label: Option<String>,
+    pub words: Vec<String>,
+}
+
+// Rectangle of cells kept free of words, e.g. for a logo, from its top-left to its
+// bottom-right cell inclusive
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Region {
+    pub top: usize,
+    pub left: usize,
+    pub bottom: usize,
+    pub right: usize,
+}
+
+impl Region {
+    pub fn contains(&self, row: usize, col: usize) -> bool {
+        (self.top..=self.bottom).contains(&row) && (self.left..=self.right).contains(&col)
+    }
+
+    pub fn area(&self) -> usize {
+        (self.bottom - self.top + 1) * (self.right - self.left + 1)
+    }
+}
+
+impl std::str::FromStr for Region {
+    type Err = String;
+
+    /// Parse `R1,C1,R2,C2`: the rows and columns of two opposite corners, in either order.
+    ///
+    /// ```
+    /// use wordsearch_generator::Region;
+    ///
+    /// let region: Region = "4,6,1,2".parse().unwrap();
+    /// assert_eq!(region, Region { top: 1, left: 2, bottom: 4, right: 6 });
+    /// assert!(region.contains(1, 6) && !region.contains(0, 6));
+    /// assert_eq!(region.area(), 20);
+    /// assert!("1,2,3".parse::<Region>().is_err());
+    /// ```
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let numbers = text.split(',')
+            .map(|part| part.trim().parse::<usize>().map_err(|e| format!("{:?}: {}", part.trim(), e)))
+            .collect::<Result<Vec<_>, _>>()?;
+        let [r1, c1, r2, c2] = numbers[..] else {
+            return Err(format!("expected R1,C1,R2,C2 but got {} number(s)", numbers.len()));
+        };
+        Ok(Region { top: r1.min(r2), left: c1.min(c2), bottom: r1.max(r2), right: c1.max(c2) })
+    }
+}
+
+// Fixed position of a pinned word: the cell of its first letter and its direction
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Pin {
+    pub row: usize,
+    pub col: usize,
+    pub direction: Direction,
+}
+
+impl WordLists {
+    /// Build word lists in code rather than loading them from YAML.
+    ///
+    /// ```
+    /// use wordsearch_generator::{RenderStyle, WordLists, WordSearchGenerator};
+    ///
+    /// let words = WordLists::new(
+    ///     vec!["ONE".to_string(), "TWO".to_string()],
+    ///     vec!["TEN".to_string(), "NINE".to_string()],
+    /// );
+    /// let generator = WordSearchGenerator::new(words, true);
+    /// let (grid, placed_words) = generator.generate(100).expect("words should fit");
+    ///
+    /// assert_eq!(placed_words.len(), 4);
+    /// grid.print(&placed_words, &RenderStyle::default());
+    /// ```
+    pub fn new(horizontal: Vec<String>, vertical: Vec<String>) -> Self {
+        Self { horizontal, vertical, title: None, instructions: None, forbidden_crossings: Vec::new(), required_crossings: Vec::new(), pinned: HashMap::new(), groups: HashMap::new(), words: Vec::new() }
+    }
+
+    /// Build word lists from a single list, sharing the words between the horizontal
+    /// and vertical directions with [`WordLists::split_words`].
+    ///
+    /// ```
+    /// use wordsearch_generator::WordLists;
+    ///
+    /// let words = ["ELEPHANT", "GIRAFFE", "ZEBRA", "LION", "BEAR", "CAT"];
+    /// let lists = WordLists::from_combined(words.iter().map(|w| w.to_string()).collect());
+    ///
+    /// let letters = |list: &[String]| list.iter().map(|w| w.len()).sum::<usize>();
+    /// assert_eq!(lists.horizontal.len(), lists.vertical.len());
+    /// assert!(letters(&lists.horizontal).abs_diff(letters(&lists.vertical)) <= 3);
+    /// ```
+    pub fn from_combined(words: Vec<String>) -> Self {
+        let mut word_lists = Self::new(Vec::new(), Vec::new());
+        word_lists.words = words;
+        word_lists.split_words();
+        word_lists
+    }
+
+    /// Check that every word has a letter to place. [`WordSearchGenerator::new`] leaves
+    /// empty words (`""`, or only whitespace and hyphens) out; this names them instead,
+    /// by list and position counting from 1.
+    ///
+    /// ```
+    /// use wordsearch_generator::WordLists;
+    ///
+    /// let words = WordLists::new(vec!["CAT".to_string(), " ".to_string()], vec!["TOP".to_string()]);
+    /// assert_eq!(words.validate(), Err("empty words in the word lists: horizontal #2 \" \"".to_string()));
+    ///
+    /// let words = WordLists::new(vec!["CAT".to_string()], vec!["TOP".to_string()]);
+    /// assert_eq!(words.validate(), Ok(()));
+    /// ```
+    pub fn validate(&self) -> Result<(), String> {
+        let is_empty = |word: &String| strip_separators(word.trim()).is_empty();
+        let mut empty: Vec<String> = [("horizontal", &self.horizontal), ("vertical", &self.vertical), ("words", &self.words)]
+            .into_iter()
+            .flat_map(|(list, words)| words.iter().enumerate()
+                .filter(|(_, word)| is_empty(word))
+                .map(move |(index, word)| format!("{} #{} {:?}", list, index + 1, word)))
+            .collect();
+        let mut pinned: Vec<String> = self.pinned.keys().filter(|word| is_empty(word)).map(|word| format!("pinned {:?}", word)).collect();
+        pinned.sort();
+        empty.extend(pinned);
+        if !empty.is_empty() {
+            return Err(format!("empty words in the word lists: {}", empty.join(", ")));
+        }
+        Ok(())
+    }
+
+    // Move the words of `words` into the horizontal and vertical lists, longest first,
+    // each to the side that keeps the two directions closest in total letters and then
+    // in word count. A side is only chosen over the other for balance when the word
+    // still shares a letter with some word of the opposite direction there, so it has
+    // something to cross. Words already in either list are skipped
+    pub fn split_words(&mut self) {
+        let mut words = std::mem::take(&mut self.words);
+        let length = |word: &String| strip_separators(word.trim()).chars().count();
+        words.sort_by_key(|word| Reverse(length(word)));
+
+        let letters = |list: &[String]| list.iter().map(length).sum::<usize>();
+        let mut totals = [letters(&self.horizontal), letters(&self.vertical)];
+        for word in words {
+            if self.horizontal.contains(&word) || self.vertical.contains(&word) {
+                continue;
+            }
+            let word_letters: HashSet<char> = strip_separators(word.trim()).chars().collect();
+            let partners = |list: &[String]| list.iter()
+                .filter(|other| other.chars().any(|c| word_letters.contains(&c)))
+                .count();
+            let counts = [self.horizontal.len(), self.vertical.len()];
+            let choice = |side: usize| {
+                let mut new_totals = totals;
+                let mut new_counts = counts;
+                new_totals[side] += length(&word);
+                new_counts[side] += 1;
+                let opposite = if side == 0 { &self.vertical } else { &self.horizontal };
+                let partners = partners(opposite);
+                (partners == 0, new_totals[0].abs_diff(new_totals[1]),
+                 new_counts[0].abs_diff(new_counts[1]), Reverse(partners))
+            };
+            let side = if choice(1) < choice(0) { 1 } else { 0 };
+            totals[side] += length(&word);
+            if side == 0 {
+                self.horizontal.push(word);
+            } else {
+                self.vertical.push(word);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Grid {
+    pub cells: Vec<Vec<Option<char>>>,
+    pub width: usize,
+    pub height: usize,
+    // Reject placements that cross or touch (even diagonally) another word
+    pub strict_separation: bool,
+    // Reject placements whose new letters would sit beside another word's letters or
+    // whose ends would run into a letter, as neither is allowed in a crossword
+    pub valid_crossword: bool,
+    // Empty cells kept around the used region when rendering
+    pub margin: usize,
+    // Cells words may occupy when the grid has a shape, indexed [row][col]. None
+    // leaves every cell open
+    pub mask: Option<Vec<Vec<bool>>>,
+    // Render from the top-left cell rather than the first used row and column, so
+    // positions given in the input stay where they were asked to be
+    pub keep_origin: bool,
+    // Render every cell, empty borders included, rather than just the used region
+    pub uncropped: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlacedWord {
+    pub word: String,
+    // Top/left-most cell of the word
+    pub start_row: usize,
+    pub start_col: usize,
+    pub direction: Direction,
+    // Letters run right-to-left (or bottom-to-top) from the far end back to start
+    pub reversed: bool,
+    // Form shown in word lists and answer keys when it differs from the placed letters,
+    // e.g. "NEW YORK" for "NEWYORK"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display: Option<String>,
+    // Extra hidden copy of a word placed back to front (see include_reversed)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub mirrored: bool,
+    // Theme label from the input's groups, if the word belongs to one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+}
+
+// ANSI colors used by --color
+const ANSI_HORIZONTAL: &str = "\x1b[36m"; // cyan
+const ANSI_VERTICAL: &str = "\x1b[33m"; // yellow
+const ANSI_INTERSECTION: &str = "\x1b[1;35m"; // bold magenta
+const ANSI_RESET: &str = "\x1b[0m";
+
+// ANSI styles used by print_with_solution
+const ANSI_WORD_LETTER: &str = "\x1b[1m"; // bold
+const ANSI_FILLER: &str = "\x1b[2m"; // dim
+
+/// Whether terminal styling may be used: standard output is a terminal and `NO_COLOR`
+/// is unset or empty. Both `--color` and `print_with_solution` go through this check.
+pub fn color_allowed() -> bool {
+    std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none_or(|v| v.is_empty())
+}
+
+// Space left around the content of each PDF page, in points
+const PDF_MARGIN: f64 = 50.0;
+
+// Multiplier on the intersection bonus for crossings on a preferred letter
+const PREFERRED_LETTER_BOOST: f64 = 4.0;
+
+// Largest size_factor accepted. Beyond it the grid is mostly empty and each attempt
+// scores far more positions for no better layout
+const MAX_SIZE_FACTOR: f64 = 10.0;
+
+// Size multipliers for the standard-algorithm retries once every regular stage has failed
+const ESCALATION_MULTIPLIERS: [f64; 2] = [1.5, 2.0];
+
+// Best candidate positions the backtracking search tries for each word before
+// undoing the previous word
+const BACKTRACK_BRANCHING: usize = 6;
+
+// Ratio of the preferred to the other side of the estimated grid under a growth bias
+const GROWTH_BIAS_ASPECT: f64 = 1.5;
+
+// Score penalty per row (or column) a placement adds in the dimension a growth bias
+// keeps small
+const GROWTH_BIAS_PENALTY: f64 = 20.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    Horizontal, // left-to-right unless reversed
+    Vertical,   // top-to-bottom unless reversed
+}
+
+impl Direction {
+    // The other of the two directions
+    fn perpendicular(self) -> Self {
+        match self {
+            Direction::Horizontal => Direction::Vertical,
+            Direction::Vertical => Direction::Horizontal,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Intersection {
+    h_word_idx: usize,
+    v_word_idx: usize,
+    h_char_idx: usize,
+    v_char_idx: usize,
+    character: char,
+}
+
+// Intersection ranked by its potential score. Ties go to the intersection found
+// first, so the ranking matches a stable sort of the full list
+#[derive(Debug, Clone)]
+struct RankedIntersection {
+    score: f64,
+    order: usize,
+    intersection: Intersection,
+}
+
+impl PartialEq for RankedIntersection {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == std::cmp::Ordering::Equal
+    }
+}
+
+impl Eq for RankedIntersection {}
+
+impl PartialOrd for RankedIntersection {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for RankedIntersection {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.score.total_cmp(&other.score).then(other.order.cmp(&self.order))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct PlacementCandidate {
+    direction: Direction,
+    row: usize,
+    col: usize,
+    score: f64,
+}
+
+impl PlacedWord {
+    /// A word reading forwards from its top/left-most cell at (`start_row`, `start_col`),
+    /// with no display form, mirroring or group.
+    ///
+    /// ```
+    /// use wordsearch_generator::{Direction, PlacedWord};
+    ///
+    /// let cat = PlacedWord::new("CAT", 1, 2, Direction::Horizontal);
+    /// assert_eq!(cat.cells().collect::<Vec<_>>(), [(1, 2), (1, 3), (1, 4)]);
+    /// ```
+    pub fn new(word: impl Into<String>, start_row: usize, start_col: usize, direction: Direction) -> Self {
+        Self { word: word.into(), start_row, start_col, direction, reversed: false, display: None, mirrored: false, group: None }
+    }
+
+    /// A word reading forwards with its last letter at (`row`, `col`), the anchor
+    /// `Grid::place_word` takes.
+    ///
+    /// # Panics
+    ///
+    /// If the word is empty or would run off the top or left edge of the grid.
+    ///
+    /// ```
+    /// use wordsearch_generator::{Direction, PlacedWord};
+    ///
+    /// let cat = PlacedWord::ending_at("CAT", 4, 2, Direction::Vertical);
+    /// assert_eq!((cat.start_row, cat.start_col), (2, 2));
+    /// assert_eq!(cat.end_position(), (4, 2));
+    /// ```
+    pub fn ending_at(word: impl Into<String>, row: usize, col: usize, direction: Direction) -> Self {
+        let word = word.into();
+        let (start_row, start_col) = placement::word_cells(word.chars().count(), row, col, direction == Direction::Horizontal)
+            .and_then(|mut cells| cells.next())
+            .expect("the word fits with its last letter at the anchor");
+        Self::new(word, start_row, start_col, direction)
+    }
+
+    // The word as it should be shown to the solver
+    pub fn display_word(&self) -> &str {
+        self.display.as_deref().unwrap_or(&self.word)
+    }
+
+    // Position of the last letter of the word
+    pub fn end_position(&self) -> (usize, usize) {
+        let last = self.word.chars().count().saturating_sub(1);
+        self.cell_at(if self.reversed { 0 } else { last })
+    }
+
+    // Iterate over the (row, col) of every cell occupied by the word, in letter order
+    pub fn cells(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let len = self.word.chars().count();
+        (0..len).map(move |i| self.cell_at(if self.reversed { len - 1 - i } else { i }))
+    }
+
+    // Arrow pointing from the first letter towards the last
+    pub fn arrow(&self) -> char {
+        match (self.direction, self.reversed) {
+            (Direction::Horizontal, false) => '→',
+            (Direction::Horizontal, true) => '←',
+            (Direction::Vertical, false) => '↓',
+            (Direction::Vertical, true) => '↑',
+        }
+    }
+
+    // Compact answer key entry: first letter coordinates, arrow and length, e.g. "OCEAN: (3,5) → 5"
+    pub fn arrow_notation(&self, (row, col): (usize, usize)) -> String {
+        format!("{}: ({},{}) {} {}", self.display_word(), row, col, self.arrow(), self.word.chars().count())
+    }
+
+    // Cell at the given offset from the top/left-most cell
+    fn cell_at(&self, offset: usize) -> (usize, usize) {
+        match self.direction {
+            Direction::Horizontal => (self.start_row, self.start_col + offset),
+            Direction::Vertical => (self.start_row + offset, self.start_col),
+        }
+    }
+}
+
+/// Letters of a word or phrase as placed in the grid, without any spaces or hyphens.
+///
+/// ```
+/// use wordsearch_generator::strip_separators;
+///
+/// assert_eq!(strip_separators("WELL-BEING"), "WELLBEING");
+/// assert_eq!(strip_separators("ICE CREAM"), "ICECREAM");
+/// ```
+pub fn strip_separators(word: &str) -> String {
+    word.chars().filter(|&c| !is_separator(c)).collect()
+}
+
+/// Characters kept in the displayed form of a word but not placed in the grid.
+///
+/// ```
+/// use wordsearch_generator::is_separator;
+///
+/// assert!(is_separator(' ') && is_separator('-'));
+/// assert!(!is_separator('A'));
+/// ```
+pub fn is_separator(c: char) -> bool {
+    c.is_whitespace() || c == '-'
+}
+
+// (height, width) covering both of two grids lined up at their first cell
+fn combined_size<T>(grid: &[Vec<Option<T>>], other: &[Vec<Option<T>>]) -> (usize, usize) {
+    let width = |rows: &[Vec<Option<T>>]| rows.iter().map(Vec::len).max().unwrap_or(0);
+    (grid.len().max(other.len()), width(grid).max(width(other)))
+}
+
+// (row, col) of every cell whose contents differ between two grids lined up at their
+// first cell, over the larger of the two
+fn changed_cells<T: PartialEq>(grid: &[Vec<Option<T>>], other: &[Vec<Option<T>>]) -> Vec<(usize, usize)> {
+    fn cell<T>(rows: &[Vec<Option<T>>], r: usize, c: usize) -> Option<&T> {
+        rows.get(r).and_then(|row| row.get(c)).and_then(Option::as_ref)
+    }
+    let (height, width) = combined_size(grid, other);
+    (0..height)
+        .flat_map(|r| (0..width).map(move |c| (r, c)))
+        .filter(|&(r, c)| cell(grid, r, c) != cell(other, r, c))
+        .collect()
+}
+
+// Share of the cells, over the larger of two grids, whose contents differ: 0 for two
+// empty grids
+fn changed_share<T: PartialEq>(grid: &[Vec<Option<T>>], other: &[Vec<Option<T>>]) -> f64 {
+    let (height, width) = combined_size(grid, other);
+    if height * width == 0 {
+        return 0.0;
+    }
+    changed_cells(grid, other).len() as f64 / (height * width) as f64
+}
+
+// Levenshtein distance: the number of single letter insertions, deletions and substitutions turning a into b
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b_chars: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b_chars.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b_chars.len() + 1];
+        for (j, &b_char) in b_chars.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != b_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b_chars.len()]
+}
+
+// Indices into placed_words of the words covering each occupied cell
+fn cell_owners(placed_words: &[PlacedWord]) -> HashMap<(usize, usize), Vec<usize>> {
+    let mut owners: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+    for (idx, word) in placed_words.iter().enumerate() {
+        for cell in word.cells() {
+            owners.entry(cell).or_default().push(idx);
+        }
+    }
+    owners
+}
+
+// Cells a word of `len` letters would occupy with its last letter at (row, col)
+fn placement_cells(len: usize, row: usize, col: usize, direction: Direction) -> impl Iterator<Item = (usize, usize)> {
+    placement::word_cells(len, row, col, direction == Direction::Horizontal).into_iter().flatten()
+}
+
+// The words of one list not marked used, each paired with the list's direction
+fn unused_words<'a>(words: &'a [String], used: &'a [bool], direction: Direction) -> impl Iterator<Item = (&'a String, Direction)> {
+    words.iter().zip(used).filter(|&(_, &used)| !used).map(move |(word, _)| (word, direction))
+}
+
+// Rows (for GrowthBias::Wide) or columns (Tall) that a word of `len` letters with its
+// last letter at (row, col) adds beyond the used area (min_row, max_row, min_col, max_col)
+fn growth_outside(bias: GrowthBias, used: (usize, usize, usize, usize), len: usize, row: usize, col: usize,
+                  direction: Direction) -> usize {
+    let (min_row, max_row, min_col, max_col) = used;
+    let (first, last, used_min, used_max) = match (bias, direction) {
+        (GrowthBias::Wide, Direction::Horizontal) => (row, row, min_row, max_row),
+        (GrowthBias::Wide, Direction::Vertical) => (row + 1 - len, row, min_row, max_row),
+        (GrowthBias::Tall, Direction::Horizontal) => (col + 1 - len, col, min_col, max_col),
+        (GrowthBias::Tall, Direction::Vertical) => (col, col, min_col, max_col),
+    };
+    used_min.saturating_sub(first) + last.saturating_sub(used_max)
+}
+
+// Pairs (inner, outer) of indices into placed_words where every cell of the inner
+// word is also a cell of the outer one, running the same way - e.g. CAT laid over
+// the start of CATALOG - so the inner word has no placement of its own
+fn nested_words(placed_words: &[PlacedWord]) -> Vec<(usize, usize)> {
+    let owners = cell_owners(placed_words);
+    let mut nested = Vec::new();
+    for (inner, word) in placed_words.iter().enumerate() {
+        let len = word.word.chars().count();
+        let mut shared_cells: HashMap<usize, usize> = HashMap::new();
+        for cell in word.cells() {
+            for &owner in &owners[&cell] {
+                if owner != inner && placed_words[owner].direction == word.direction {
+                    *shared_cells.entry(owner).or_default() += 1;
+                }
+            }
+        }
+        nested.extend(shared_cells.into_iter().filter(|&(_, shared)| shared == len).map(|(outer, _)| (inner, outer)));
+    }
+    nested.sort_unstable();
+    nested
+}
+
+// Whether each occupied cell is covered by a (horizontal, vertical) word
+fn direction_coverage(placed_words: &[PlacedWord]) -> HashMap<(usize, usize), (bool, bool)> {
+    let mut coverage: HashMap<(usize, usize), (bool, bool)> = HashMap::new();
+    for word in placed_words {
+        for cell in word.cells() {
+            let entry = coverage.entry(cell).or_default();
+            match word.direction {
+                Direction::Horizontal => entry.0 = true,
+                Direction::Vertical => entry.1 = true,
+            }
+        }
+    }
+    coverage
+}
+
+// True if some maximal run of two or more adjacent letters in a row or column is not
+// exactly the cells of a placed word running that way, e.g. two parallel words side by
+// side forming two-letter fragments across them
+fn has_stray_run(placed_words: &[PlacedWord]) -> bool {
+    let occupied: HashSet<(usize, usize)> = placed_words.iter().flat_map(|word| word.cells()).collect();
+    let words: HashSet<(Direction, (usize, usize), usize)> = placed_words.iter()
+        .filter_map(|word| Some((word.direction, word.cells().min()?, word.cells().count())))
+        .collect();
+    occupied.iter().any(|&(row, col)| {
+        [Direction::Horizontal, Direction::Vertical].into_iter().any(|direction| {
+            let step = |(r, c): (usize, usize)| match direction {
+                Direction::Horizontal => (r, c + 1),
+                Direction::Vertical => (r + 1, c),
+            };
+            let starts_run = match direction {
+                Direction::Horizontal => col == 0 || !occupied.contains(&(row, col - 1)),
+                Direction::Vertical => row == 0 || !occupied.contains(&(row - 1, col)),
+            };
+            if !starts_run {
+                return false;
+            }
+            let len = std::iter::successors(Some((row, col)), |&cell| Some(step(cell)))
+                .take_while(|cell| occupied.contains(cell))
+                .count();
+            len >= 2 && !words.contains(&(direction, (row, col), len))
+        })
+    })
+}
+
+// Longer side over shorter side of the region the placed words cover; 1 when square
+fn aspect_ratio(placed_words: &[PlacedWord]) -> f64 {
+    let cells: Vec<(usize, usize)> = placed_words.iter().flat_map(|word| word.cells()).collect();
+    let extent = |values: Vec<usize>| values.iter().max().zip(values.iter().min()).map_or(0, |(max, min)| max - min + 1);
+    let height = extent(cells.iter().map(|&(row, _)| row).collect());
+    let width = extent(cells.iter().map(|&(_, col)| col).collect());
+    if height == 0 || width == 0 {
+        return 1.0;
+    }
+    height.max(width) as f64 / height.min(width) as f64
+}
+
+/// Number of cells where a horizontal and a vertical word cross.
+pub fn count_crossings(placed_words: &[PlacedWord]) -> usize {
+    direction_coverage(placed_words).into_values().filter(|&covered| covered == (true, true)).count()
+}
+
+/// Number of placed words of the other direction that pass through one of the word's
+/// cells; a letter elsewhere in the same row or column does not count. Each crossing
+/// pair counts once for each of its two words, so moving one word changes the sum over
+/// all words by twice the change in its own count, which is how annealing keeps that
+/// sum up to date. Two overlapping words crossing it at one cell count twice here,
+/// while `max_intersections_per_word` counts the cell once.
+///
+/// ```
+/// use wordsearch_generator::{word_crossings, Direction, PlacedWord};
+///
+/// let placed = |word: &str, row, col, direction| PlacedWord::new(word, row, col, direction);
+/// // CAT across the top, TOP down from its T, and AT lower down in the same columns
+/// let words = [placed("CAT", 0, 0, Direction::Horizontal), placed("TOP", 0, 2, Direction::Vertical),
+///              placed("AT", 3, 1, Direction::Horizontal)];
+///
+/// assert_eq!(word_crossings(&words[0], &words), 1);
+/// assert_eq!(word_crossings(&words[1], &words), 1);
+/// assert_eq!(word_crossings(&words[2], &words), 0);
+/// ```
+pub fn word_crossings(word: &PlacedWord, placed_words: &[PlacedWord]) -> usize {
+    let len = word.word.chars().count();
+    placed_words.iter()
+        .filter(|other| other.direction != word.direction)
+        .filter(|other| {
+            let other_len = other.word.chars().count();
+            match word.direction {
+                Direction::Horizontal => (word.start_col..word.start_col + len).contains(&other.start_col)
+                    && (other.start_row..other.start_row + other_len).contains(&word.start_row),
+                Direction::Vertical => (word.start_row..word.start_row + len).contains(&other.start_row)
+                    && (other.start_col..other.start_col + other_len).contains(&word.start_col),
+            }
+        })
+        .count()
+}
+
+// Number of cells of each placed word that are also covered by a word in the
+// other direction, in the same order as placed_words
+fn crossings_per_word(placed_words: &[PlacedWord]) -> Vec<usize> {
+    let coverage = direction_coverage(placed_words);
+    placed_words.iter()
+        .map(|word| word.cells().filter(|cell| coverage[cell] == (true, true)).count())
+        .collect()
+}
+
+// Most crossings possible between horizontal and vertical words of the given lengths,
+// where only the listed (horizontal, vertical) pairs can cross, each at most once, and
+// a word has no more crossings than letters. Solved as a maximum flow
+// source -> horizontal word -> vertical word -> sink with augmenting paths
+fn max_crossings(h_lens: &[usize], v_lens: &[usize], pairs: &[(usize, usize)]) -> usize {
+    let source = 0;
+    let sink = 1;
+    let h_node = |h: usize| 2 + h;
+    let v_node = |v: usize| 2 + h_lens.len() + v;
+    let node_count = 2 + h_lens.len() + v_lens.len();
+
+    // Each edge is stored next to its reverse, so edge ^ 1 is the opposite direction
+    let mut targets = Vec::new();
+    let mut capacities = Vec::new();
+    let mut edges_from = vec![Vec::new(); node_count];
+    let mut add_edge = |from: usize, to: usize, capacity: usize| {
+        edges_from[from].push(targets.len());
+        targets.push(to);
+        capacities.push(capacity);
+        edges_from[to].push(targets.len());
+        targets.push(from);
+        capacities.push(0);
+    };
+    for (h, &len) in h_lens.iter().enumerate() {
+        add_edge(source, h_node(h), len);
+    }
+    for (v, &len) in v_lens.iter().enumerate() {
+        add_edge(v_node(v), sink, len);
+    }
+    for &(h, v) in pairs {
+        add_edge(h_node(h), v_node(v), 1);
+    }
+
+    let mut flow = 0;
+    loop {
+        // Breadth-first search for a path with spare capacity, remembering the edge used
+        // to reach each node
+        let mut reached_by: Vec<Option<usize>> = vec![None; node_count];
+        let mut queue = VecDeque::from([source]);
+        while let Some(node) = queue.pop_front() {
+            for &edge in &edges_from[node] {
+                let next = targets[edge];
+                if capacities[edge] > 0 && next != source && reached_by[next].is_none() {
+                    reached_by[next] = Some(edge);
+                    queue.push_back(next);
+                }
+            }
+        }
+        if reached_by[sink].is_none() {
+            return flow;
+        }
+
+        // Every edge into a word node carries at most the word length and the pair edges
+        // carry one, so push a single unit along the path
+        let mut node = sink;
+        while let Some(edge) = reached_by[node] {
+            capacities[edge] -= 1;
+            capacities[edge ^ 1] += 1;
+            node = targets[edge ^ 1];
+        }
+        flow += 1;
+    }
+}
+
+// Length of the longest run of open cells along a row (horizontal) or column (vertical)
+fn longest_open_run(mask: &[Vec<bool>], direction: Direction) -> usize {
+    let width = mask.first().map_or(0, |row| row.len());
+    let lines: Vec<Vec<bool>> = match direction {
+        Direction::Horizontal => mask.to_vec(),
+        Direction::Vertical => (0..width).map(|c| mask.iter().map(|row| row[c]).collect()).collect(),
+    };
+    lines.iter()
+        .map(|line| {
+            let mut longest = 0;
+            let mut run = 0;
+            for &open in line {
+                run = if open { run + 1 } else { 0 };
+                longest = longest.max(run);
+            }
+            longest
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+impl Grid {
+    pub fn new(width: usize, height: usize) -> Self {
+        Grid {
+            cells: vec![vec![None; width]; height],
+            width,
+            height,
+            strict_separation: false,
+            valid_crossword: false,
+            margin: 0,
+            mask: None,
+            keep_origin: false,
+            uncropped: false,
+        }
+    }
+
+    // True if words may use the cell, i.e. it is inside the grid's shape
+    fn is_open(&self, row: usize, col: usize) -> bool {
+        self.mask.as_ref().is_none_or(|mask| mask[row][col])
+    }
+
+    /// Check whether `word` fits with its last letter at (`row`, `col`). The letters read
+    /// left-to-right (or top-to-bottom) but are laid out back from the anchor, so it
+    /// must be at least `len - 1` cells from the left/top edge.
+    ///
+    /// ```
+    /// use wordsearch_generator::{Direction, Grid};
+    ///
+    /// let grid = Grid::new(5, 5);
+    ///
+    /// // A single letter fits in the first column and row
+    /// assert!(grid.can_place_word("A", 0, 0, Direction::Horizontal));
+    /// assert!(grid.can_place_word("A", 0, 0, Direction::Vertical));
+    ///
+    /// // A word as long as the grid fits only when anchored at the last column/row
+    /// assert!(grid.can_place_word("ABCDE", 2, 4, Direction::Horizontal));
+    /// assert!(!grid.can_place_word("ABCDE", 2, 3, Direction::Horizontal));
+    /// assert!(grid.can_place_word("ABCDE", 4, 2, Direction::Vertical));
+    /// assert!(!grid.can_place_word("ABCDE", 3, 2, Direction::Vertical));
+    ///
+    /// // One cell too far left/up is rejected, as is an anchor outside the grid
+    /// assert!(!grid.can_place_word("ABC", 0, 1, Direction::Horizontal));
+    /// assert!(!grid.can_place_word("ABC", 1, 0, Direction::Vertical));
+    /// assert!(!grid.can_place_word("ABC", 0, 5, Direction::Horizontal));
+    /// assert!(!grid.can_place_word("ABC", 5, 0, Direction::Vertical));
+    /// ```
+    pub fn can_place_word(&self, word: &str, row: usize, col: usize, direction: Direction) -> bool {
+        placement::fits(&self.cells, word, row, col, direction == Direction::Horizontal, |r, c| {
+            self.is_open(r, c) && !(self.strict_separation && self.touches_placed_letter(r, c))
+        }) && !(self.valid_crossword && self.forms_stray_run(word, row, col, direction))
+    }
+
+    // True if placing the word would put one of its new letters next to a letter across
+    // its direction, or leave a letter just before its first or after its last cell.
+    // Crossing cells already hold their letter, so their neighbors belong to the word
+    // being crossed
+    fn forms_stray_run(&self, word: &str, row: usize, col: usize, direction: Direction) -> bool {
+        let horizontal = direction == Direction::Horizontal;
+        let len = word.chars().count();
+        let filled = |r: Option<usize>, c: Option<usize>| match (r, c) {
+            (Some(r), Some(c)) if r < self.height && c < self.width => self.cells[r][c].is_some(),
+            _ => false,
+        };
+        let (first_row, first_col) = if horizontal { (row, col + 1 - len) } else { (row + 1 - len, col) };
+        let before_first = if horizontal {
+            filled(Some(row), first_col.checked_sub(1))
+        } else {
+            filled(first_row.checked_sub(1), Some(col))
+        };
+        let after_last = if horizontal { filled(Some(row), Some(col + 1)) } else { filled(Some(row + 1), Some(col)) };
+        before_first || after_last || placement::word_cells(len, row, col, horizontal).into_iter().flatten()
+            .filter(|&(r, c)| self.cells[r][c].is_none())
+            .any(|(r, c)| if horizontal {
+                filled(r.checked_sub(1), Some(c)) || filled(Some(r + 1), Some(c))
+            } else {
+                filled(Some(r), c.checked_sub(1)) || filled(Some(r), Some(c + 1))
+            })
+    }
+
+    // Number of the word's letters that would land on letters already in the grid.
+    // Assumes the placement has already been checked with can_place_word
+    fn count_shared_letters(&self, word: &str, row: usize, col: usize, direction: Direction) -> usize {
+        let len = word.chars().count();
+        (0..len)
+            .map(|i| match direction {
+                Direction::Horizontal => (row, col + 1 - len + i),
+                Direction::Vertical => (row + 1 - len + i, col),
+            })
+            .filter(|&(r, c)| self.cells[r][c].is_some())
+            .count()
+    }
+
+    // True if the cell or any of its eight neighbors holds a letter. The word being
+    // checked is not on the grid yet, so any letter found belongs to a different word
+    fn touches_placed_letter(&self, row: usize, col: usize) -> bool {
+        let rows = row.saturating_sub(1)..=(row + 1).min(self.height - 1);
+        rows.into_iter().any(|r| {
+            let cols = col.saturating_sub(1)..=(col + 1).min(self.width - 1);
+            cols.into_iter().any(|c| self.cells[r][c].is_some())
+        })
+    }
+
+    pub fn place_word(&mut self, word: &str, row: usize, col: usize, direction: Direction) -> bool {
+        if !self.can_place_word(word, row, col, direction) {
+            return false;
+        }
+        placement::write(&mut self.cells, word, row, col, direction == Direction::Horizontal);
+        true
+    }
+
+    /// Clear the word's letters from the grid, keeping those shared with the remaining
+    /// words, so taking a word out never breaks a word that crosses it.
+    ///
+    /// ```
+    /// use wordsearch_generator::{Direction, Grid, PlacedWord};
+    ///
+    /// let placed = |word: &str, row, col, direction| PlacedWord::new(word, row, col, direction);
+    /// let mut grid = Grid::new(3, 3);
+    /// grid.place_word("CAT", 0, 2, Direction::Horizontal);
+    /// grid.place_word("TOP", 2, 2, Direction::Vertical);
+    ///
+    /// let top = [placed("TOP", 0, 2, Direction::Vertical)];
+    /// grid.remove_word(&placed("CAT", 0, 0, Direction::Horizontal), &top);
+    /// assert_eq!(grid.cells[0], [None, None, Some('T')]);
+    /// assert!(grid.misplaced_words(&top).is_empty());
+    /// ```
+    pub fn remove_word(&mut self, word: &PlacedWord, remaining_words: &[PlacedWord]) {
+        let shared: HashSet<(usize, usize)> = remaining_words.iter().flat_map(|other| other.cells()).collect();
+        for (row, col) in word.cells() {
+            if row < self.height && col < self.width && !shared.contains(&(row, col)) {
+                self.cells[row][col] = None;
+            }
+        }
+    }
+
+    /// Smallest (min_row, max_row, min_col, max_col) rectangle holding every letter,
+    /// or `None` for an empty grid. Does not modify the grid.
+    pub fn bounding_box(&self) -> Option<(usize, usize, usize, usize)> {
+        let mut bounds: Option<(usize, usize, usize, usize)> = None;
+
+        for (r, row) in self.cells.iter().enumerate() {
+            for (c, cell) in row.iter().enumerate() {
+                if cell.is_some() {
+                    bounds = Some(match bounds {
+                        Some((min_row, max_row, min_col, max_col)) =>
+                            (min_row.min(r), max_row.max(r), min_col.min(c), max_col.max(c)),
+                        None => (r, r, c, c),
+                    });
+                }
+            }
+        }
+
+        bounds
+    }
+
+    // Bounding box, with (0, 0, 0, 0) standing in for an empty grid
+    pub fn calculate_used_area(&self) -> (usize, usize, usize, usize) {
+        self.bounding_box().unwrap_or((0, 0, 0, 0))
+    }
+
+    // (height, width) of the bounding box, (0, 0) for an empty grid
+    pub fn get_used_dimensions(&self) -> (usize, usize) {
+        self.bounding_box()
+            .map_or((0, 0), |(min_row, max_row, min_col, max_col)| (max_row - min_row + 1, max_col - min_col + 1))
+    }
+
+    /// Fraction of cells whose letter differs between the used regions of two grids,
+    /// lined up at their top-left corners and compared over the larger of the two: 0 for
+    /// the same layout wherever it sits in each grid, 1 when no cell matches.
+    ///
+    /// ```
+    /// use wordsearch_generator::{Direction, Grid};
+    ///
+    /// let mut first = Grid::new(4, 2);
+    /// first.place_word("CAT", 0, 2, Direction::Horizontal);
+    /// let mut shifted = Grid::new(4, 2);
+    /// shifted.place_word("CAT", 1, 3, Direction::Horizontal);
+    /// let mut other = Grid::new(4, 2);
+    /// other.place_word("COT", 0, 2, Direction::Horizontal);
+    ///
+    /// assert_eq!(first.difference(&shifted), 0.0);
+    /// assert!((first.difference(&other) - 1.0 / 3.0).abs() < 1e-9);
+    /// ```
+    pub fn difference(&self, other: &Grid) -> f64 {
+        let used_region = |grid: &Grid| grid.bounding_box().map_or_else(Vec::new, |(min_row, max_row, min_col, max_col)| {
+            grid.cells[min_row..=max_row].iter().map(|row| row[min_col..=max_col].to_vec()).collect()
+        });
+        changed_share(&used_region(self), &used_region(other))
+    }
+
+    // Bounding box widened by the margin, clamped to the grid. A shaped grid is
+    // shown whole so the outline stays intact, as is an uncropped one
+    fn render_area(&self) -> Option<(usize, usize, usize, usize)> {
+        let (min_row, max_row, min_col, max_col) = self.bounding_box()?;
+        if self.mask.is_some() || self.uncropped {
+            return Some((0, self.height - 1, 0, self.width - 1));
+        }
+        let (min_row, min_col) = if self.keep_origin { (0, 0) } else { (min_row, min_col) };
+        Some((min_row.saturating_sub(self.margin), (max_row + self.margin).min(self.height - 1),
+              min_col.saturating_sub(self.margin), (max_col + self.margin).min(self.width - 1)))
+    }
+
+    // A cell as reported in the text and HTML output and the answer keys: counted from
+    // the origin over the rows and columns up to the last rendered ones, so the default
+    // top-left origin keeps the grid's own indices
+    pub fn user_cell(&self, cell: (usize, usize), origin: Origin) -> (usize, usize) {
+        let (_, max_row, _, max_col) = self.render_area().unwrap_or((0, 0, 0, 0));
+        origin.locate(cell, (0, max_row, 0, max_col))
+    }
+
+    // Reported cell of a word's first letter
+    pub fn first_letter_cell(&self, word: &PlacedWord, origin: Origin) -> (usize, usize) {
+        self.user_cell(word.cells().next().unwrap_or((word.start_row, word.start_col)), origin)
+    }
+
+    // Surround the grid with k empty rows and columns on every side, shifting the placed
+    // words to match, and keep them when rendering
+    pub fn add_margin(&mut self, k: usize, placed_words: &mut [PlacedWord]) {
+        let new_width = self.width + 2 * k;
+        let mut new_cells = vec![vec![None; new_width]; k];
+        for row in &self.cells {
+            let mut new_row = vec![None; k];
+            new_row.extend_from_slice(row);
+            new_row.resize(new_width, None);
+            new_cells.push(new_row);
+        }
+        new_cells.resize(self.height + 2 * k, vec![None; new_width]);
+
+        // The margin lies outside any shape
+        if let Some(mask) = &mut self.mask {
+            let mut new_mask = vec![vec![false; new_width]; k];
+            for row in mask.iter() {
+                let mut new_row = vec![false; k];
+                new_row.extend_from_slice(row);
+                new_row.resize(new_width, false);
+                new_mask.push(new_row);
+            }
+            new_mask.resize(self.height + 2 * k, vec![false; new_width]);
+            *mask = new_mask;
+        }
+
+        self.cells = new_cells;
+        self.width = new_width;
+        self.height += 2 * k;
+        self.margin += k;
+
+        for word in placed_words.iter_mut() {
+            word.start_row += k;
+            word.start_col += k;
+        }
+    }
+
+    // Crop the grid to its bounding box. An empty grid is left as it is
+    pub fn compact(&mut self) -> (usize, usize) {
+        let Some((min_row, max_row, min_col, max_col)) = self.bounding_box() else {
+            return (0, 0);
+        };
+        
+        // Create a new compacted grid
+        let new_height = max_row - min_row + 1;
+        let new_width = max_col - min_col + 1;
+        let mut new_cells = vec![vec![None; new_width]; new_height];
+        
+        for (r, new_row) in new_cells.iter_mut().enumerate() {
+            new_row.copy_from_slice(&self.cells[min_row + r][min_col..min_col + new_width]);
+        }
+        if let Some(mask) = &mut self.mask {
+            *mask = mask[min_row..=max_row].iter().map(|row| row[min_col..=max_col].to_vec()).collect();
+        }
+        
+        self.cells = new_cells;
+        self.width = new_width;
+        self.height = new_height;
+        
+        (min_row, min_col) // Return offset for updating word positions
+    }
+
+    // Remove empty rows/columns, shifting the placed words that lie beyond them. An
+    // empty row or column can never be spanned by a word so no word is split
+    pub fn try_remove_empty_rows_cols(&mut self, placed_words: &mut [PlacedWord]) -> bool {
+        let mut changed = false;
+        
+        // Try to remove empty rows
+        let mut row = 0;
+        while row < self.height {
+            let row_empty = (0..self.width).all(|c| self.cells[row][c].is_none());
+            if row_empty {
+                self.cells.remove(row);
+                if let Some(mask) = &mut self.mask {
+                    mask.remove(row);
+                }
+                self.height -= 1;
+                for word in placed_words.iter_mut().filter(|w| w.start_row > row) {
+                    word.start_row -= 1;
+                }
+                changed = true;
+            } else {
+                row += 1;
+            }
+        }
+        
+        // Try to remove empty columns
+        let mut col = 0;
+        while col < self.width {
+            let col_empty = (0..self.height).all(|r| self.cells[r][col].is_none());
+            if col_empty {
+                for row in &mut self.cells {
+                    row.remove(col);
+                }
+                for row in self.mask.iter_mut().flatten() {
+                    row.remove(col);
+                }
+                self.width -= 1;
+                for word in placed_words.iter_mut().filter(|w| w.start_col > col) {
+                    word.start_col -= 1;
+                }
+                changed = true;
+            } else {
+                col += 1;
+            }
+        }
+        
+        changed
+    }
+
+    // Flip the whole grid horizontally and/or vertically, moving the placed words
+    // with it. A word whose letters get mirrored along its own axis is marked reversed
+    pub fn reorient(&mut self, orientation: Orientation, placed_words: &mut [PlacedWord]) {
+        let (flip_h, flip_v) = match orientation {
+            Orientation::Normal => (false, false),
+            Orientation::FlipH => (true, false),
+            Orientation::FlipV => (false, true),
+            Orientation::Rotate180 => (true, true),
+        };
+
+        if flip_h {
+            for row in &mut self.cells {
+                row.reverse();
+            }
+            for row in self.mask.iter_mut().flatten() {
+                row.reverse();
+            }
+        }
+        if flip_v {
+            self.cells.reverse();
+            if let Some(mask) = &mut self.mask {
+                mask.reverse();
+            }
+        }
+
+        for word in placed_words.iter_mut() {
+            let last = word.word.chars().count().saturating_sub(1);
+            let (end_row, end_col) = word.cell_at(last);
+            if flip_h {
+                word.start_col = self.width - 1 - end_col;
+                if word.direction == Direction::Horizontal {
+                    word.reversed = !word.reversed;
+                }
+            }
+            if flip_v {
+                word.start_row = self.height - 1 - end_row;
+                if word.direction == Direction::Vertical {
+                    word.reversed = !word.reversed;
+                }
+            }
+        }
+    }
+
+    // Swap the rows and columns of the whole grid, moving the placed words with it.
+    // Horizontal words become vertical and vice versa, keeping their reading order
+    pub fn transpose(&mut self, placed_words: &mut [PlacedWord]) {
+        self.cells = (0..self.width)
+            .map(|col| self.cells.iter().map(|row| row[col]).collect())
+            .collect();
+        if let Some(mask) = &mut self.mask {
+            *mask = (0..self.width)
+                .map(|col| mask.iter().map(|row| row[col]).collect())
+                .collect();
+        }
+        std::mem::swap(&mut self.width, &mut self.height);
+
+        for word in placed_words.iter_mut() {
+            std::mem::swap(&mut word.start_row, &mut word.start_col);
+            word.direction = word.direction.perpendicular();
+        }
+    }
+
+    // Record which directions contribute to each cell: (horizontal, vertical)
+    fn cell_directions(&self, placed_words: &[PlacedWord]) -> Vec<Vec<(bool, bool)>> {
+        let mut directions = vec![vec![(false, false); self.width]; self.height];
+
+        for word in placed_words {
+            for (r, c) in word.cells() {
+                if r >= self.height || c >= self.width {
+                    continue;
+                }
+                match word.direction {
+                    Direction::Horizontal => directions[r][c].0 = true,
+                    Direction::Vertical => directions[r][c].1 = true,
+                }
+            }
+        }
+
+        directions
+    }
+
+    // Mark the cells holding the first letter of a placed word
+    fn word_start_cells(&self, placed_words: &[PlacedWord]) -> Vec<Vec<bool>> {
+        let mut starts = vec![vec![false; self.width]; self.height];
+
+        for word in placed_words {
+            if let Some((row, col)) = word.cells().next() {
+                if row < self.height && col < self.width {
+                    starts[row][col] = true;
+                }
+            }
+        }
+
+        starts
+    }
+
+    // Apply the requested output case to a single letter. In title case a cell is
+    // upper case if it starts any word passing through it - so at an intersection
+    // the letter is capitalized when either crossing word begins there
+    fn render_letter(ch: char, output_case: Option<OutputCase>, is_word_start: bool) -> String {
+        let converted = match output_case {
+            None => return ch.to_string(),
+            Some(OutputCase::Upper) => ch.to_uppercase().collect(),
+            Some(OutputCase::Lower) => ch.to_lowercase().collect(),
+            Some(OutputCase::Title) if is_word_start => ch.to_uppercase().collect(),
+            Some(OutputCase::Title) => ch.to_lowercase().collect::<String>(),
+        };
+        // Some letters change case into several (e.g. 'ß' to "SS"), which would put two
+        // letters in one cell, so those are left as they are
+        if converted.chars().count() == 1 { converted } else { ch.to_string() }
+    }
+
+    // Lay out the used region as text cells padded to a common width so columns line
+    // up whatever the content (letters, multi-digit coordinates, markers)
+    fn layout(&self, placed_words: &[PlacedWord], style: &RenderStyle) -> TextLayout {
+        let Some((min_row, max_row, min_col, max_col)) = self.render_area() else {
+            return TextLayout { rows: Vec::new(), row_labels: Vec::new(), col_labels: Vec::new(), cell_width: 1 };
+        };
+        let directions = if style.color { Some(self.cell_directions(placed_words)) } else { None };
+        let starts = self.word_start_cells(placed_words);
+
+        let mut rows = Vec::new();
+        for r in min_row..=max_row {
+            let mut row = Vec::new();
+            for c in min_col..=max_col {
+                let cell = match self.cells[r][c] {
+                    Some(ch) => LayoutCell {
+                        text: Self::render_letter(ch, style.output_case, starts[r][c]),
+                        color: match directions.as_ref().map(|d| d[r][c]) {
+                            Some((true, true)) => Some(ANSI_INTERSECTION),
+                            Some((true, false)) => Some(ANSI_HORIZONTAL),
+                            Some((false, true)) => Some(ANSI_VERTICAL),
+                            _ => None,
+                        },
+                        filled: true,
+                    },
+                    None if !self.is_open(r, c) => LayoutCell { text: " ".to_string(), color: None, filled: false },
+                    None => LayoutCell { text: style.empty_char.unwrap_or('.').to_string(), color: None, filled: false },
+                };
+                row.push(cell);
+            }
+            rows.push(row);
+        }
+
+        let (row_labels, col_labels) = if style.coords {
+            ((min_row..=max_row).map(|r| self.user_cell((r, min_col), style.origin).0.to_string()).collect(),
+             (min_col..=max_col).map(|c| self.user_cell((min_row, c), style.origin).1.to_string()).collect())
+        } else {
+            (Vec::new(), Vec::new())
+        };
+
+        let cell_width = rows.iter()
+            .flatten()
+            .map(|cell| cell.text.chars().count())
+            .chain(col_labels.iter().map(|label: &String| label.chars().count()))
+            .max()
+            .unwrap_or(1);
+
+        TextLayout { rows, row_labels, col_labels, cell_width }
+    }
+
+    // Used region as CSV, one grid row per line. Empty cells are blank fields
+    // unless an empty character is configured
+    pub fn to_csv(&self, placed_words: &[PlacedWord], style: &RenderStyle) -> String {
+        let Some((min_row, max_row, min_col, max_col)) = self.render_area() else {
+            return String::new();
+        };
+        let starts = self.word_start_cells(placed_words);
+        let mut output = String::new();
+
+        for (r, row) in (min_row..).zip(&self.cells[min_row..=max_row]) {
+            let fields: Vec<String> = (min_col..).zip(&row[min_col..=max_col])
+                .map(|(c, &cell)| {
+                    let text = match cell {
+                        Some(ch) => Self::render_letter(ch, style.output_case, starts[r][c]),
+                        None if !self.is_open(r, c) => String::new(),
+                        None => style.empty_char.map(String::from).unwrap_or_default(),
+                    };
+                    csv_field(&text)
+                })
+                .collect();
+            output.push_str(&fields.join(","));
+            output.push('\n');
+        }
+
+        output
+    }
+
+    /// Graphviz DOT graph with a node per word (boxes for horizontal words, ellipses for
+    /// vertical ones) and an edge per crossing, labeled with the shared letter and its
+    /// (row, col) in the rendered grid. Hidden reverse copies are left out.
+    ///
+    /// ```
+    /// use wordsearch_generator::{RenderStyle, WordLists, WordSearchGenerator};
+    ///
+    /// let words = WordLists::new(vec!["CAT".to_string()], vec!["TOP".to_string(), "DOG".to_string()]);
+    /// let generator = WordSearchGenerator::new(words, true);
+    /// let (grid, placed_words) = generator.generate(50).expect("words should fit");
+    /// let dot = grid.to_dot(&placed_words, &RenderStyle::default(), Some("Pets"));
+    ///
+    /// assert!(dot.starts_with("graph crossings {"));
+    /// assert_eq!(dot.matches(" -- ").count(), 1);
+    /// assert!(dot.contains("label=\"T "));
+    /// ```
+    pub fn to_dot(&self, placed_words: &[PlacedWord], style: &RenderStyle, title: Option<&str>) -> String {
+        let area = self.render_area().unwrap_or((0, 0, 0, 0));
+        let words: Vec<&PlacedWord> = placed_words.iter().filter(|word| !word.mirrored).collect();
+        let mut dot = String::from("graph crossings {\n");
+        if let Some(title) = title {
+            dot.push_str(&format!("  label=\"{}\";\n  labelloc=t;\n", dot_escape(title)));
+        }
+        for (index, word) in words.iter().enumerate() {
+            let shape = match word.direction {
+                Direction::Horizontal => "box",
+                Direction::Vertical => "ellipse",
+            };
+            dot.push_str(&format!("  w{} [label=\"{}\", shape={}];\n", index, dot_escape(word.display_word()), shape));
+        }
+        for (first_index, first) in words.iter().enumerate() {
+            for (second_index, second) in words.iter().enumerate().skip(first_index + 1) {
+                if first.direction == second.direction {
+                    continue;
+                }
+                for (row, col) in first.cells().filter(|cell| second.cells().any(|other| other == *cell)) {
+                    let letter = self.cells[row][col].map(|ch| Self::render_letter(ch, style.output_case, false)).unwrap_or_default();
+                    let (row, col) = style.origin.locate((row, col), area);
+                    dot.push_str(&format!("  w{} -- w{} [label=\"{} ({}, {})\"];\n", first_index, second_index,
+                                          dot_escape(&letter), row, col));
+                }
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    // Answer key as an HTML table or list, in the requested style
+    fn html_answer_key(&self, placed_words: &[PlacedWord], style: &RenderStyle) -> String {
+        let mut html = String::new();
+        match style.answer_key_style {
+            AnswerKeyStyle::Table => {
+                html.push_str("<table>\n<tr><th>Word</th><th>Direction</th><th>Row</th><th>Column</th></tr>\n");
+                for word in placed_words {
+                    let (row, col) = self.first_letter_cell(word, style.origin);
+                    html.push_str(&format!("<tr><td>{}</td><td>{:?}{}{}</td><td>{}</td><td>{}</td></tr>\n",
+                                           html_escape(word.display_word()), word.direction,
+                                           if word.reversed { " (reversed)" } else { "" },
+                                           if word.mirrored { " (hidden reverse copy)" } else { "" }, row, col));
+                }
+                html.push_str("</table>\n");
+            }
+            AnswerKeyStyle::Arrows => {
+                html.push_str("<ul class=\"words\">\n");
+                for word in placed_words {
+                    html.push_str(&format!("<li>{}</li>\n", html_escape(&word.arrow_notation(self.first_letter_cell(word, style.origin)))));
+                }
+                html.push_str("</ul>\n");
+            }
+        }
+        html
+    }
+
+    // Self-contained printable HTML page: optional title, the grid as a CSS grid, the
+    // word list, optional instructions and an optional collapsible answer key. With
+    // Sections::Grid only the grid is included, with Sections::Solution only the answer key
+    pub fn to_html(&self, placed_words: &[PlacedWord], style: &RenderStyle, title: Option<&str>, instructions: Option<&str>,
+                   word_bank: &[WordBankGroup]) -> String {
+        let plain_style = RenderStyle { color: false, coords: false, ..style.clone() };
+        let layout = self.layout(placed_words, &plain_style);
+
+        // Cells are tinted by the group of the first grouped word covering them, with
+        // colors numbered in word bank order
+        let group_class = |label: &Option<String>| {
+            word_bank.iter()
+                .filter(|group| group.label.is_some())
+                .position(|group| group.label == *label && label.is_some())
+                .map(|index| format!(" group-{}", index % GROUP_COLORS))
+                .unwrap_or_default()
+        };
+        let (min_row, _, min_col, _) = self.render_area().unwrap_or((0, 0, 0, 0));
+        let mut cell_groups: HashMap<(usize, usize), String> = HashMap::new();
+        for word in placed_words {
+            let class = group_class(&word.group);
+            if class.is_empty() {
+                continue;
+            }
+            for cell in word.cells() {
+                cell_groups.entry(cell).or_insert_with(|| class.clone());
+            }
+        }
+        let columns = layout.rows.first().map_or(0, |row| row.len());
+        let mut html = String::new();
+
+        html.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+        html.push_str(&format!("<title>{}</title>\n", html_escape(title.unwrap_or("Word Search"))));
+        html.push_str(HTML_STYLE);
+        html.push_str("</head>\n<body>\n");
+
+        if let (Some(title), Sections::All) = (title, style.sections) {
+            html.push_str(&format!("<h1>{}</h1>\n", html_escape(title)));
+        }
+
+        if style.sections == Sections::Solution {
+            let heading = title.map_or("Answer key".to_string(), |title| format!("{}: answer key", title));
+            html.push_str(&format!("<h1>{}</h1>\n", html_escape(&heading)));
+            html.push_str(&self.html_answer_key(placed_words, style));
+            html.push_str("</body>\n</html>\n");
+            return html;
+        }
+
+        html.push_str(&format!("<div class=\"grid\" style=\"grid-template-columns: repeat({}, 2em);\">\n", columns));
+        for (r, row) in layout.rows.iter().enumerate() {
+            for (c, cell) in row.iter().enumerate() {
+                if cell.filled {
+                    let class = cell_groups.get(&(min_row + r, min_col + c)).map_or("", String::as_str);
+                    html.push_str(&format!("<div class=\"cell{}\">{}</div>", class, html_escape(&cell.text)));
+                } else {
+                    html.push_str("<div class=\"cell empty\"></div>");
+                }
+            }
+        }
+        html.push_str("\n</div>\n");
+
+        if style.sections == Sections::Grid {
+            html.push_str("</body>\n</html>\n");
+            return html;
+        }
+
+        let sectioned = word_bank.iter().any(|group| group.label.is_some());
+        for group in word_bank {
+            if sectioned {
+                html.push_str(&format!("<h2>{}</h2>\n", html_escape(group.label.as_deref().unwrap_or("Other"))));
+            }
+            html.push_str(&format!("<ul class=\"words{}\">\n", group_class(&group.label)));
+            for word in &group.words {
+                html.push_str(&format!("<li>{}</li>\n", html_escape(word)));
+            }
+            html.push_str("</ul>\n");
+        }
+
+        if let Some(instructions) = instructions {
+            html.push_str(&format!("<p class=\"instructions\">{}</p>\n", html_escape(instructions)));
+        }
+
+        if style.answer_key {
+            html.push_str("<details class=\"answers\">\n<summary>Answer key</summary>\n");
+            html.push_str(&self.html_answer_key(placed_words, style));
+            html.push_str("</details>\n");
+        }
+
+        html.push_str("</body>\n</html>\n");
+        html
+    }
+
+    // Collect the used region of the grid and the placed words for serialization
+    pub fn export<'a>(&self, placed_words: &'a [PlacedWord], style: &RenderStyle, title: Option<&'a str>,
+                      instructions: Option<&'a str>, stats: Option<&'a GenerationStats>) -> PuzzleExport<'a> {
+        let plain_style = RenderStyle { color: false, coords: false, ..style.clone() };
+        let layout = self.layout(placed_words, &plain_style);
+        let mut grid: Vec<Vec<Option<String>>> = layout.rows.into_iter()
+            .map(|row| row.into_iter().map(|cell| cell.filled.then_some(cell.text)).collect())
+            .collect();
+        style.origin.arrange(&mut grid);
+        // Starts stay in the grid's own indices, where they read with direction and
+        // reversed as everywhere else; the cells index the exported grid from the origin
+        let area = self.render_area();
+        let words = placed_words.iter()
+            .map(|word| ExportedWord {
+                word,
+                cells: area.map_or_else(Vec::new, |area| word.cells().map(|cell| style.origin.locate(cell, area)).collect()),
+            })
+            .collect();
+
+        PuzzleExport {
+            title,
+            instructions,
+            origin: style.origin,
+            width: grid.first().map_or(0, |row| row.len()),
+            height: grid.len(),
+            grid,
+            words,
+            stats,
+        }
+    }
+
+    // Just the letters and the word bank, for the solver's copy of a puzzle
+    pub fn export_grid<'a>(&self, placed_words: &[PlacedWord], style: &RenderStyle, title: Option<&'a str>,
+                           instructions: Option<&'a str>, word_bank: &'a [String]) -> PuzzleGridExport<'a> {
+        let export = self.export(placed_words, style, title, instructions, None);
+        PuzzleGridExport {
+            title,
+            instructions,
+            origin: export.origin,
+            width: export.width,
+            height: export.height,
+            grid: export.grid,
+            words: word_bank,
+        }
+    }
+
+    /// The answer key as data: every word with the cells it covers, indexed like the
+    /// `grid` of [`Grid::export`] and [`Grid::export_grid`] for the same puzzle, so a
+    /// client holding only the grid can highlight each word.
+    ///
+    /// ```
+    /// use wordsearch_generator::{Direction, Grid, Origin, PlacedWord, RenderStyle};
+    ///
+    /// let mut grid = Grid::new(4, 3);
+    /// grid.place_word("CAT", 1, 3, Direction::Horizontal);
+    /// let placed_words = vec![PlacedWord::new("CAT", 1, 1, Direction::Horizontal)];
+    ///
+    /// // The exported grid is cropped to the used row, so the word is at row 0, from column 0
+    /// let solution = grid.export_solution(&placed_words, &RenderStyle::default(), None);
+    /// assert_eq!((solution.width, solution.height), (3, 1));
+    /// assert_eq!(solution.words[0].cells, vec![(0, 0), (0, 1), (0, 2)]);
+    ///
+    /// // Counting from the right, the word reads from column 2 towards column 0
+    /// let style = RenderStyle { origin: Origin::TopRight, ..RenderStyle::default() };
+    /// let solution = grid.export_solution(&placed_words, &style, None);
+    /// assert_eq!(solution.words[0].cells, vec![(0, 2), (0, 1), (0, 0)]);
+    /// ```
+    pub fn export_solution<'a>(&self, placed_words: &'a [PlacedWord], style: &RenderStyle, title: Option<&'a str>)
+        -> PuzzleSolutionExport<'a> {
+        let Some(area) = self.render_area() else {
+            return PuzzleSolutionExport { title, origin: style.origin, width: 0, height: 0, words: Vec::new() };
+        };
+        let (min_row, max_row, min_col, max_col) = area;
+        let words = placed_words.iter()
+            .map(|word| SolutionWord {
+                word: &word.word,
+                display: word.display.as_deref(),
+                direction: word.direction,
+                reversed: word.reversed,
+                mirrored: word.mirrored,
+                cells: word.cells().map(|cell| style.origin.locate(cell, area)).collect(),
+            })
+            .collect();
+
+        PuzzleSolutionExport {
+            title,
+            origin: style.origin,
+            width: max_col - min_col + 1,
+            height: max_row - min_row + 1,
+            words,
+        }
+    }
+
+    // Printable PDF: title, grid, word bank and instructions on the first page and,
+    // if requested, the answer key from a new page. A word bank, instructions or answer
+    // key too long for the page carries on over further pages. Sections::Grid keeps
+    // just the grid and Sections::Solution just the answer key
+    pub fn to_pdf(&self, placed_words: &[PlacedWord], style: &RenderStyle, title: Option<&str>, instructions: Option<&str>,
+                  word_bank: &[String], page_size: PageSize) -> Vec<u8> {
+        let plain_style = RenderStyle { color: false, coords: false, ..style.clone() };
+        let layout = self.layout(placed_words, &plain_style);
+
+        let mut flow = PdfFlow::new(page_size);
+        if style.sections != Sections::Solution {
+            self.pdf_puzzle(&mut flow, &layout, style, title, instructions, word_bank);
+        }
+        if style.answer_key || style.sections == Sections::Solution {
+            if style.sections != Sections::Solution {
+                flow.new_page();
+            }
+            let heading = match (title, style.sections) {
+                (Some(title), Sections::Solution) => format!("{}: answer key", title),
+                _ => "Answer key".to_string(),
+            };
+            let y = flow.line(20.0);
+            flow.page().text(PDF_MARGIN, y, 20.0, PdfFont::Helvetica, &heading);
+            for (i, line) in self.answer_key_text(placed_words, style).lines().enumerate() {
+                let y = flow.line(if i == 0 { 30.0 } else { 14.0 });
+                flow.page().text(PDF_MARGIN, y, 10.0, PdfFont::Courier, line);
+            }
+        }
+
+        pdf_document(&flow.pages, page_size)
+    }
+
+    // The puzzle part of a PDF: title, grid, word bank and instructions, or just the
+    // grid with Sections::Grid
+    fn pdf_puzzle(&self, flow: &mut PdfFlow, layout: &TextLayout, style: &RenderStyle, title: Option<&str>,
+                  instructions: Option<&str>, word_bank: &[String]) {
+        let (page_width, page_height) = flow.page_size.points();
+        let text_width = page_width - 2.0 * PDF_MARGIN;
+        let all_sections = style.sections == Sections::All;
+
+        if let (Some(title), true) = (title, all_sections) {
+            let y = flow.line(20.0);
+            flow.page().text(PDF_MARGIN, y, 20.0, PdfFont::Helvetica, title);
+            flow.skip(16.0);
+        }
+
+        // The grid gets at most 60% of the page height so the word bank fits below it
+        let geometry = GridGeometry::fitted(layout, text_width, 0.6 * (page_height - 2.0 * PDF_MARGIN), 28.0);
+        let cell = geometry.cell;
+        let font_size = cell * 0.6;
+        let grid_top = flow.y;
+        for (r, row) in layout.rows.iter().enumerate() {
+            for (c, layout_cell) in row.iter().enumerate() {
+                let (x, down) = geometry.corner(r, c);
+                let (left, top) = (PDF_MARGIN + x, grid_top - down);
+                if layout_cell.filled {
+                    flow.page().rect(left, top - cell, cell, cell);
+                }
+                if !layout_cell.filled && style.empty_char.is_none() {
+                    continue;
+                }
+                // Helvetica capitals are roughly 0.7em wide and 0.7em tall
+                let text_width = layout_cell.text.chars().count() as f64 * font_size * 0.7;
+                flow.page().text(left + (cell - text_width) / 2.0, top - (cell + font_size * 0.7) / 2.0,
+                                 font_size, PdfFont::Helvetica, &layout_cell.text);
+            }
+        }
+        flow.skip(geometry.size().1 + 14.0);
+        if !all_sections {
+            return;
+        }
+
+        // Word bank in three columns
+        let column_width = text_width / 3.0;
+        for line in word_bank.chunks(3) {
+            let y = flow.line(16.0);
+            for (i, word) in line.iter().enumerate() {
+                flow.page().text(PDF_MARGIN + i as f64 * column_width, y, 12.0, PdfFont::Helvetica, word);
+            }
+        }
+
+        // Helvetica averages about half an em per character
+        let line_chars = (text_width / 6.0) as usize;
+        for (i, line) in instructions.map(|text| wrap_text(text, line_chars)).unwrap_or_default().iter().enumerate() {
+            let y = flow.line(if i == 0 { 30.0 } else { 16.0 });
+            flow.page().text(PDF_MARGIN, y, 12.0, PdfFont::Helvetica, line);
+        }
+    }
+
+    // Standalone SVG drawing of the used region, one outlined square per letter. Cells of
+    // the highlighted word are shaded, e.g. to mark the newest word in a placement frame
+    pub fn to_svg(&self, placed_words: &[PlacedWord], style: &RenderStyle, highlight: Option<&PlacedWord>) -> String {
+        let plain_style = RenderStyle { color: false, coords: false, ..style.clone() };
+        let layout = self.layout(placed_words, &plain_style);
+        let (min_row, _, min_col, _) = self.render_area().unwrap_or((0, 0, 0, 0));
+        let highlighted: HashSet<(usize, usize)> = highlight.map(|word| word.cells().collect()).unwrap_or_default();
+        let geometry = GridGeometry::new(&layout, 32.0);
+        let (width, height) = geometry.size();
+        let cell_size = geometry.cell;
+
+        let mut svg = format!("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{1}\" viewBox=\"0 0 {0} {1}\">\n",
+                              width, height);
+        svg.push_str(&format!("<rect width=\"{}\" height=\"{}\" fill=\"white\"/>\n", width, height));
+        for (r, row) in layout.rows.iter().enumerate() {
+            for (c, cell) in row.iter().enumerate() {
+                let (x, y) = geometry.corner(r, c);
+                if cell.filled {
+                    let fill = if highlighted.contains(&(min_row + r, min_col + c)) { "#ffe680" } else { "none" };
+                    svg.push_str(&format!("<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\" stroke=\"#999\"/>\n",
+                                          x, y, cell_size, cell_size, fill));
+                } else if style.empty_char.is_none() {
+                    continue;
+                }
+                svg.push_str(&format!("<text x=\"{}\" y=\"{}\" font-family=\"sans-serif\" font-size=\"20\" text-anchor=\"middle\" \
+                                       dominant-baseline=\"central\">{}</text>\n",
+                                      x + cell_size / 2.0, y + cell_size / 2.0, html_escape(&cell.text)));
+            }
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// The grid as it builds up word by word, in placement order: frame `i` holds the
+    /// first `i + 1` placed words on an empty grid of this size, drawn whole so every
+    /// frame has the same dimensions.
+    ///
+    /// ```
+    /// use wordsearch_generator::{Algorithm, GenerationConfig, WordLists, WordSearchGenerator};
+    ///
+    /// let words = WordLists::new(vec!["CAT".to_string()], vec!["TEA".to_string(), "ACE".to_string()]);
+    /// let mut generator = WordSearchGenerator::new(words, true);
+    /// generator.set_config(GenerationConfig { algorithm: Some(Algorithm::Greedy), ..GenerationConfig::default() });
+    /// let (grid, placed_words) = generator.generate(1).expect("words should fit");
+    /// let frames = grid.placement_frames(&placed_words);
+    ///
+    /// assert_eq!(frames.len(), placed_words.len());
+    /// assert_eq!(frames.last().unwrap().cells, grid.cells);
+    /// assert!(frames.iter().all(|frame| (frame.width, frame.height) == (grid.width, grid.height)));
+    /// ```
+    pub fn placement_frames(&self, placed_words: &[PlacedWord]) -> Vec<Grid> {
+        let mut frame = Grid::new(self.width, self.height);
+        frame.uncropped = true;
+        placed_words.iter()
+            .map(|word| {
+                for ((row, col), ch) in word.cells().zip(word.word.chars()) {
+                    frame.cells[row][col] = Some(ch);
+                }
+                frame.clone()
+            })
+            .collect()
+    }
+
+    // Used region as text, one grid row per line
+    pub fn to_text(&self, placed_words: &[PlacedWord], style: &RenderStyle) -> String {
+        self.layout(placed_words, style).render()
+    }
+
+    /// Placed words whose cells, read from the first letter in the word's direction, do
+    /// not spell the word - i.e. its recorded position disagrees with the grid. Empty
+    /// for every correctly generated puzzle, whichever algorithm produced it:
+    ///
+    /// ```
+    /// use clap::ValueEnum;
+    /// use wordsearch_generator::{Algorithm, GenerationConfig, WordLists, WordSearchGenerator};
+    ///
+    /// let words = WordLists::new(
+    ///     vec!["THREE".to_string(), "FOUR".to_string(), "ONE".to_string()],
+    ///     vec!["SEVEN".to_string(), "EIGHT".to_string(), "TEN".to_string()],
+    /// );
+    /// let mut generator = WordSearchGenerator::new(words, true);
+    /// for &algorithm in Algorithm::value_variants() {
+    ///     generator.set_config(GenerationConfig { include_reversed: true, algorithm: Some(algorithm), ..GenerationConfig::default() });
+    ///     let (grid, placed_words) = generator.generate(20).expect("words should fit");
+    ///     assert!(grid.misplaced_words(&placed_words).is_empty(), "{:?} misplaced a word", algorithm);
+    /// }
+    /// ```
+    pub fn misplaced_words<'a>(&self, placed_words: &'a [PlacedWord]) -> Vec<&'a PlacedWord> {
+        placed_words.iter()
+            .filter(|word| {
+                let mut cells = word.cells();
+                !word.word.chars().all(|ch| {
+                    cells.next().is_some_and(|(r, c)| self.cells.get(r).and_then(|row| row.get(c)) == Some(&Some(ch)))
+                })
+            })
+            .collect()
+    }
+
+    // Answer key as plain text, one word per line
+    pub fn answer_key_text(&self, placed_words: &[PlacedWord], style: &RenderStyle) -> String {
+        let mut text = String::new();
+        for word in placed_words {
+            let line = match style.answer_key_style {
+                AnswerKeyStyle::Table => {
+                    let (row, col) = self.first_letter_cell(word, style.origin);
+                    let direction = format!("{:?}{}{}", word.direction, if word.reversed { " (reversed)" } else { "" },
+                                            if word.mirrored { " (hidden copy)" } else { "" });
+                    format!("{:<16} {:<22} {:>3} {:>3}", word.display_word(), direction, row, col)
+                }
+                AnswerKeyStyle::Arrows => word.arrow_notation(self.first_letter_cell(word, style.origin)),
+            };
+            text.push_str(&line);
+            text.push('\n');
+        }
+        text
+    }
+
+    // The text of to_text as a Display value, for writing to any sink with format! or write!
+    pub fn display<'a>(&'a self, placed_words: &'a [PlacedWord], style: &'a RenderStyle) -> GridDisplay<'a> {
+        GridDisplay { grid: self, placed_words, style }
+    }
+
+    pub fn print(&self, placed_words: &[PlacedWord], style: &RenderStyle) {
+        print!("{}", self.display(placed_words, style));
+    }
+
+    /// Grid text for showing a solved puzzle: the letters of placed words in bold and
+    /// every other cell, filler letters and empty cells alike, dimmed so the words stand
+    /// out. Plain text when `color` is false.
+    ///
+    /// ```
+    /// use wordsearch_generator::{Direction, Grid, PlacedWord};
+    ///
+    /// let mut grid = Grid::new(3, 1);
+    /// grid.place_word("CAT", 0, 2, Direction::Horizontal);
+    /// let placed_words = vec![PlacedWord::new("CAT", 0, 0, Direction::Horizontal)];
+    ///
+    /// assert_eq!(grid.to_solution_text(&placed_words, false), "C A T \n");
+    /// assert!(grid.to_solution_text(&placed_words, true).starts_with("\x1b[1mC"));
+    /// ```
+    pub fn to_solution_text(&self, placed_words: &[PlacedWord], color: bool) -> String {
+        let mut layout = self.layout(placed_words, &RenderStyle::default());
+        if let (true, Some((min_row, _, min_col, _))) = (color, self.render_area()) {
+            let owners = cell_owners(placed_words);
+            for (r, row) in layout.rows.iter_mut().enumerate() {
+                for (c, cell) in row.iter_mut().enumerate() {
+                    let owned = owners.contains_key(&(min_row + r, min_col + c));
+                    cell.color = Some(if owned { ANSI_WORD_LETTER } else { ANSI_FILLER });
+                }
+            }
+        }
+        layout.render()
+    }
+
+    // Print to_solution_text, styled only when color_allowed
+    pub fn print_with_solution(&self, placed_words: &[PlacedWord]) {
+        print!("{}", self.to_solution_text(placed_words, color_allowed()));
+    }
+}
+
+// Quote a CSV field if it contains a separator, quote or line break
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+// Escape text for use in a quoted DOT string
+fn dot_escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+// Escape text for use in HTML element content or attribute values
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[derive(Debug, Clone, Copy)]
+enum PdfFont {
+    Helvetica,
+    Courier,
+}
+
+// Content stream of a single PDF page, drawn with the standard Helvetica and Courier fonts
+#[derive(Debug, Default)]
+struct PdfPage {
+    content: Vec<u8>,
+}
+
+impl PdfPage {
+    fn text(&mut self, x: f64, y: f64, size: f64, font: PdfFont, text: &str) {
+        let font = match font {
+            PdfFont::Helvetica => "F1",
+            PdfFont::Courier => "F2",
+        };
+        self.content.extend_from_slice(format!("BT /{} {:.2} Tf {:.2} {:.2} Td (", font, size, x, y).as_bytes());
+        self.content.extend(pdf_string(text));
+        self.content.extend_from_slice(b") Tj ET\n");
+    }
+
+    fn rect(&mut self, x: f64, y: f64, width: f64, height: f64) {
+        self.content.extend_from_slice(format!("0.5 w {:.2} {:.2} {:.2} {:.2} re S\n", x, y, width, height).as_bytes());
+    }
+}
+
+// Pages of a PDF filled from top to bottom, starting a new page when the next line
+// would run into the bottom margin
+struct PdfFlow {
+    pages: Vec<PdfPage>,
+    page_size: PageSize,
+    // Baseline of the last line drawn, or the top margin on a fresh page
+    y: f64,
+}
+
+impl PdfFlow {
+    fn new(page_size: PageSize) -> Self {
+        PdfFlow { pages: vec![PdfPage::default()], page_size, y: page_size.points().1 - PDF_MARGIN }
+    }
+
+    fn new_page(&mut self) {
+        self.pages.push(PdfPage::default());
+        self.y = self.page_size.points().1 - PDF_MARGIN;
+    }
+
+    fn page(&mut self) -> &mut PdfPage {
+        self.pages.last_mut().expect("a flow always has a page")
+    }
+
+    // Move down by height and return the new baseline, on a new page if it would fall
+    // below the bottom margin
+    fn line(&mut self, height: f64) -> f64 {
+        if self.y - height < PDF_MARGIN {
+            self.new_page();
+        }
+        self.y -= height;
+        self.y
+    }
+
+    // Leave a gap of this height; a line that then no longer fits starts a new page
+    fn skip(&mut self, height: f64) {
+        self.y -= height;
+    }
+}
+
+// Break text into lines of at most max_chars characters at spaces. A single word
+// longer than that gets a line of its own
+fn wrap_text(text: &str, max_chars: usize) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for word in text.split_whitespace() {
+        match lines.last_mut() {
+            Some(line) if line.chars().count() + 1 + word.chars().count() <= max_chars => {
+                line.push(' ');
+                line.push_str(word);
+            }
+            _ => lines.push(word.to_string()),
+        }
+    }
+    lines
+}
+
+// Encode text for a PDF string literal in WinAnsiEncoding. Latin-1 characters map
+// directly, the answer key arrows become ASCII and anything else becomes '?'
+fn pdf_string(text: &str) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for ch in text.chars() {
+        match ch {
+            '(' | ')' | '\\' => bytes.extend_from_slice(&[b'\\', ch as u8]),
+            '→' => bytes.extend_from_slice(b"->"),
+            '←' => bytes.extend_from_slice(b"<-"),
+            '↓' => bytes.push(b'v'),
+            '↑' => bytes.push(b'^'),
+            ' '..='~' | '\u{a0}'..='\u{ff}' => bytes.push(ch as u8),
+            _ => bytes.push(b'?'),
+        }
+    }
+    bytes
+}
+
+// Assemble pages into a PDF file: catalog, page tree, the two fonts, then a page
+// object and content stream per page, followed by the cross-reference table
+fn pdf_document(pages: &[PdfPage], page_size: PageSize) -> Vec<u8> {
+    let (width, height) = page_size.points();
+    let page_ids: Vec<usize> = (0..pages.len()).map(|i| 5 + 2 * i).collect();
+    let kids: Vec<String> = page_ids.iter().map(|id| format!("{} 0 R", id)).collect();
+
+    let mut objects: Vec<Vec<u8>> = vec![
+        b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(),
+        format!("<< /Type /Pages /Kids [{}] /Count {} >>", kids.join(" "), pages.len()).into_bytes(),
+        b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>".to_vec(),
+        b"<< /Type /Font /Subtype /Type1 /BaseFont /Courier /Encoding /WinAnsiEncoding >>".to_vec(),
+    ];
+    for (page, id) in pages.iter().zip(&page_ids) {
+        objects.push(format!("<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] \
+                              /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> /Contents {} 0 R >>",
+                             width, height, id + 1).into_bytes());
+        let mut stream = format!("<< /Length {} >>\nstream\n", page.content.len()).into_bytes();
+        stream.extend_from_slice(&page.content);
+        stream.extend_from_slice(b"endstream");
+        objects.push(stream);
+    }
+
+    let mut pdf = b"%PDF-1.4\n".to_vec();
+    let mut offsets = Vec::new();
+    for (i, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.extend_from_slice(format!("{} 0 obj\n", i + 1).as_bytes());
+        pdf.extend_from_slice(object);
+        pdf.extend_from_slice(b"\nendobj\n");
+    }
+
+    let xref_offset = pdf.len();
+    pdf.extend_from_slice(format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).as_bytes());
+    for offset in offsets {
+        pdf.extend_from_slice(format!("{:010} 00000 n \n", offset).as_bytes());
+    }
+    pdf.extend_from_slice(format!("trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+                                  objects.len() + 1, xref_offset).as_bytes());
+    pdf
+}
+
+// Number of distinct group colors in HTML_STYLE; further groups reuse them
+const GROUP_COLORS: usize = 6;
+
+const HTML_STYLE: &str = "<style>
+body { font-family: sans-serif; margin: 2em; }
+.grid { display: grid; gap: 0; margin: 1em 0; }
+.cell { width: 2em; height: 2em; line-height: 2em; text-align: center; font-size: 1.2em; border: 1px solid #999;
+        print-color-adjust: exact; -webkit-print-color-adjust: exact; }
+.cell.empty { border-color: transparent; }
+.words { columns: 3; list-style: none; padding: 0; }
+.group-0 { background: #fde2e4; }
+.group-1 { background: #dbeafe; }
+.group-2 { background: #dcfce7; }
+.group-3 { background: #fef9c3; }
+.group-4 { background: #ede9fe; }
+.group-5 { background: #ffedd5; }
+@media print {
+  body { margin: 0; }
+  .answers { page-break-before: always; }
+  .answers:not([open]) { display: none; }
+}
+</style>
+";
+
+/// The used region as text, with the default style: `.` for empty cells, letters as
+/// placed. Use [`Grid::display`] for another style, e.g. a different empty character.
+///
+/// ```
+/// use wordsearch_generator::{Direction, Grid, RenderStyle};
+///
+/// let mut grid = Grid::new(3, 2);
+/// grid.place_word("CAT", 0, 2, Direction::Horizontal);
+/// grid.place_word("TO", 1, 2, Direction::Vertical);
+/// assert_eq!(format!("{}", grid), "C A T \n. . O \n");
+///
+/// let style = RenderStyle { empty_char: Some('-'), ..RenderStyle::default() };
+/// assert_eq!(grid.display(&[], &style).to_string(), "C A T \n- - O \n");
+/// ```
+impl std::fmt::Display for Grid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.display(&[], &RenderStyle::default()).fmt(f)
+    }
+}
+
+// Grid text in a given style, returned by Grid::display
+pub struct GridDisplay<'a> {
+    grid: &'a Grid,
+    placed_words: &'a [PlacedWord],
+    style: &'a RenderStyle,
+}
+
+impl std::fmt::Display for GridDisplay<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.grid.to_text(self.placed_words, self.style))
+    }
+}
+
+impl TextLayout {
+    fn render(&self) -> String {
+        let label_width = self.row_labels.iter().map(|label| label.len()).max().unwrap_or(0);
+        let mut output = String::new();
+
+        if !self.col_labels.is_empty() {
+            output.push_str(&format!("{:>width$} ", "", width = label_width));
+            for label in &self.col_labels {
+                output.push_str(&format!("{:<width$} ", label, width = self.cell_width));
+            }
+            output.push('\n');
+        }
+
+        for (r, row) in self.rows.iter().enumerate() {
+            if let Some(label) = self.row_labels.get(r) {
+                output.push_str(&format!("{:>width$} ", label, width = label_width));
+            }
+            for cell in row {
+                let padded = format!("{:<width$}", cell.text, width = self.cell_width);
+                match cell.color {
+                    Some(color) => output.push_str(&format!("{}{}{} ", color, padded, ANSI_RESET)),
+                    None => output.push_str(&format!("{} ", padded)),
+                }
+            }
+            output.push('\n');
+        }
+
+        output
+    }
+}
+
+// A new best solution reported while generation is still running. Scores are only
+// comparable within the same stage (higher is better)
+pub struct SolutionUpdate<'a> {
+    pub stage: &'a str,
+    pub grid: &'a Grid,
+    pub placed_words: &'a [PlacedWord],
+    pub score: f64,
+}
+
+// Wall-clock time spent running one algorithm stage
+#[derive(Debug, Clone, Serialize)]
+pub struct StageTiming {
+    pub algorithm: Algorithm,
+    pub width: usize,
+    pub height: usize,
+    pub attempts: usize,
+    // Attempts actually run, fewer than `attempts` when a good enough solution stopped the search
+    pub attempts_used: usize,
+    pub succeeded: bool,
+    pub duration: Duration,
+}
+
+// Normalized summary of how good a finished puzzle is, see WordSearchGenerator::quality
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Quality {
+    // Weighted combination of the components below, from 0 to 100
+    pub score: f64,
+    // Area lower bound divided by the area, from 0 to 1
+    pub compactness: f64,
+    // Share of the words able to cross that do cross at least one other word
+    pub connectivity: f64,
+    // Shorter side of the used area divided by the longer side
+    pub balance: f64,
+}
+
+// Measurements from the most recent generation run
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct GenerationStats {
+    pub stages: Vec<StageTiming>,
+    pub winning_algorithm: Option<Algorithm>,
+    pub annealing_time: Duration,
+    pub compaction_time: Duration,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    // Approximate smallest area any solution could have, see area_lower_bound
+    pub area_lower_bound: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quality: Option<Quality>,
+    // With all_words_connected: the words left without a crossing in the rejected
+    // solution that came closest (fewest such words)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub floating_words: Vec<String>,
+    // With max_aspect: the aspect ratio of the squarest solution rejected for exceeding it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub best_rejected_aspect: Option<f64>,
+}
+
+// How often a letter occurs across the word lists
+#[derive(Debug, Clone, Serialize)]
+pub struct LetterFrequency {
+    pub letter: char,
+    pub count: usize,
+    pub horizontal: usize,
+    pub vertical: usize,
+    pub rare: bool,
+}
+
+// Word list report produced by --analyze
+#[derive(Debug, Clone, Serialize)]
+pub struct WordListAnalysis {
+    pub letters: Vec<LetterFrequency>,
+    pub total_letters: usize,
+    pub possible_intersections: usize,
+    pub disconnected_words: Vec<String>,
+}
+
+// Serialized form of a finished puzzle
+#[derive(Debug, Serialize)]
+pub struct PuzzleExport<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instructions: Option<&'a str>,
+    // Corner the grid rows and word positions count from
+    pub origin: Origin,
+    pub width: usize,
+    pub height: usize,
+    // Rendered letters of the used region, row by row from the origin, null for empty cells
+    pub grid: Vec<Vec<Option<String>>>,
+    pub words: Vec<ExportedWord<'a>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stats: Option<&'a GenerationStats>,
+}
+
+// A placed word as exported: its placement, counted from the top-left like pins, and
+// the [row, col] of each letter in reading order, indexed like the exported grid
+#[derive(Debug, Serialize)]
+pub struct ExportedWord<'a> {
+    #[serde(flatten)]
+    pub word: &'a PlacedWord,
+    pub cells: Vec<(usize, usize)>,
+}
+
+// Serialized puzzle without its solution, for handing to solvers: the letters and the
+// words to find, but not where they are
+#[derive(Debug, Serialize)]
+pub struct PuzzleGridExport<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instructions: Option<&'a str>,
+    pub origin: Origin,
+    pub width: usize,
+    pub height: usize,
+    pub grid: Vec<Vec<Option<String>>>,
+    pub words: &'a [String],
+}
+
+// Serialized answer key matching a PuzzleGridExport of the same puzzle
+#[derive(Debug, Serialize)]
+pub struct PuzzleSolutionExport<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<&'a str>,
+    pub origin: Origin,
+    pub width: usize,
+    pub height: usize,
+    pub words: Vec<SolutionWord<'a>>,
+}
+
+// Where one word lies in the exported grid
+#[derive(Debug, Serialize)]
+pub struct SolutionWord<'a> {
+    pub word: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display: Option<&'a str>,
+    pub direction: Direction,
+    pub reversed: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub mirrored: bool,
+    // [row, col] of each letter in reading order, indexed like the exported grid
+    pub cells: Vec<(usize, usize)>,
+}
+
+impl PuzzleExport<'_> {
+    /// Compare this puzzle with a previously exported one, cell by cell and word by word.
+    /// Words are matched by their letters; a word whose position, direction or reading
+    /// order differs has moved.
+    ///
+    /// ```
+    /// use wordsearch_generator::{Direction, Grid, PlacedWord, PuzzleSnapshot, RenderStyle};
+    ///
+    /// let placed = |word: &str, row, col, direction| PlacedWord::new(word, row, col, direction);
+    /// let mut before = Grid::new(3, 2);
+    /// before.place_word("CAT", 0, 2, Direction::Horizontal);
+    /// let old_words = [placed("CAT", 0, 0, Direction::Horizontal)];
+    /// let old_json = serde_json::to_string(&before.export(&old_words, &RenderStyle::default(), None, None, None)).unwrap();
+    /// let old: PuzzleSnapshot = serde_json::from_str(&old_json).unwrap();
+    ///
+    /// // Same CAT, plus TO hanging down from its T
+    /// let mut after = before.clone();
+    /// after.place_word("TO", 1, 2, Direction::Vertical);
+    /// let new_words = [placed("CAT", 0, 0, Direction::Horizontal), placed("TO", 0, 2, Direction::Vertical)];
+    /// let diff = after.export(&new_words, &RenderStyle::default(), None, None, None).diff(&old);
+    ///
+    /// assert_eq!(diff.changed_cells, vec![(1, 2)]);
+    /// assert_eq!(diff.added_words.len(), 1);
+    /// assert!(diff.moved_words.is_empty() && diff.removed_words.is_empty());
+    /// ```
+    pub fn diff(&self, old: &PuzzleSnapshot) -> PuzzleDiff {
+        let changed_cells = changed_cells(&self.grid, &self.rows_of(old));
+
+        let same_word = |a: &PlacedWord, b: &PlacedWord| a.word == b.word && a.mirrored == b.mirrored;
+        let mut moved_words = Vec::new();
+        let mut added_words = Vec::new();
+        for word in self.words.iter().map(|exported| exported.word) {
+            match old.words.iter().find(|old_word| same_word(old_word, word)) {
+                Some(old_word) => {
+                    if (old_word.start_row, old_word.start_col, old_word.direction, old_word.reversed)
+                        != (word.start_row, word.start_col, word.direction, word.reversed) {
+                        moved_words.push((old_word.clone(), word.clone()));
+                    }
+                }
+                None => added_words.push(word.clone()),
+            }
+        }
+        let removed_words = old.words.iter()
+            .filter(|old_word| !self.words.iter().any(|exported| same_word(old_word, exported.word)))
+            .cloned()
+            .collect();
+
+        PuzzleDiff { changed_cells, moved_words, added_words, removed_words }
+    }
+
+    /// Fraction of cells, over the larger of the two grids, whose letter differs from the
+    /// earlier puzzle: 0 for the same layout, 1 when no cell matches.
+    ///
+    /// ```
+    /// use wordsearch_generator::{Direction, Grid, PlacedWord, RenderStyle};
+    ///
+    /// let placed = PlacedWord::new("CAT", 0, 0, Direction::Horizontal);
+    /// let mut grid = Grid::new(3, 1);
+    /// grid.place_word("CAT", 0, 2, Direction::Horizontal);
+    /// let words = [placed];
+    /// let export = grid.export(&words, &RenderStyle::default(), None, None, None);
+    ///
+    /// assert_eq!(export.difference(&export.snapshot()), 0.0);
+    /// ```
+    pub fn difference(&self, old: &PuzzleSnapshot) -> f64 {
+        changed_share(&self.grid, &self.rows_of(old))
+    }
+
+    // Rows of an earlier puzzle brought round to this one's origin: each corner's
+    // arrangement undoes itself, so the old one first goes back to top-left
+    fn rows_of(&self, old: &PuzzleSnapshot) -> Vec<Vec<Option<String>>> {
+        let mut rows = old.grid.clone();
+        old.origin.arrange(&mut rows);
+        self.origin.arrange(&mut rows);
+        rows
+    }
+
+    // The parts of the export that --diff reads back, without going through JSON
+    pub fn snapshot(&self) -> PuzzleSnapshot {
+        PuzzleSnapshot {
+            origin: self.origin,
+            width: self.width,
+            height: self.height,
+            grid: self.grid.clone(),
+            words: self.words.iter().map(|exported| exported.word.clone()).collect(),
+        }
+    }
+}
+
+// A puzzle read back from JSON output, for comparison with a new one
+#[derive(Debug, Clone, Deserialize)]
+pub struct PuzzleSnapshot {
+    // Output from before --origin existed counts from the top-left
+    #[serde(default)]
+    pub origin: Origin,
+    pub width: usize,
+    pub height: usize,
+    pub grid: Vec<Vec<Option<String>>>,
+    pub words: Vec<PlacedWord>,
+}
+
+// Differences between a new puzzle and an earlier snapshot
+#[derive(Debug, Clone)]
+pub struct PuzzleDiff {
+    // (row, col) of every cell whose letter differs, over the larger of the two grids
+    pub changed_cells: Vec<(usize, usize)>,
+    // (old, new) placement of each word found in both at different positions
+    pub moved_words: Vec<(PlacedWord, PlacedWord)>,
+    pub added_words: Vec<PlacedWord>,
+    pub removed_words: Vec<PlacedWord>,
+}
+
+type ImprovementCallback = Box<dyn FnMut(&SolutionUpdate)>;
+
+// A requested crossing and the (row, col) cell where it is met, if it is
+pub type CrossingCell<'a> = (&'a String, &'a String, Option<(usize, usize)>);
+
+// Settings that steer generation, apart from the words themselves. Saved as YAML, a
+// config is a reusable puzzle "recipe"; fields missing from the file keep their defaults
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GenerationConfig {
+    pub fixed_width: Option<usize>,
+    pub fixed_height: Option<usize>,
+    pub auto_grow: bool,
+    pub max_grow: usize,
+    // Order in which words are queued for placement
+    pub placement_order: PlacementOrder,
+    // How the optimized algorithm interleaves horizontal and vertical words in its queue
+    pub queue_interleave: QueueInterleave,
+    pub strict_separation: bool,
+    // Reject solutions with a run of two or more adjacent letters, across or down, that
+    // is not exactly a placed word, as a real crossword requires
+    pub valid_crossword: bool,
+    // Never let a word lie wholly on the cells of another word running the same way
+    pub forbid_nested_words: bool,
+    pub max_intersections_per_word: Option<usize>,
+    // Reject solutions in which any word crosses no other word
+    pub all_words_connected: bool,
+    // Empty cells added around the finished grid
+    pub margin: usize,
+    // Scale the intersection bonus by how rare the crossing letter is
+    pub rare_letter_bonus: bool,
+    // Letters (lowercase) whose crossings get a boosted intersection bonus
+    pub preferred_intersection_letters: BTreeSet<char>,
+    // Also hide a back-to-front copy of every word
+    pub include_reversed: bool,
+    // Score placements by distance from the other words rather than from the grid center
+    pub spread: bool,
+    pub algorithm: Option<Algorithm>,
+    // Outline the words must fit inside. The grid keeps its full size rather than being compacted
+    pub shape: Option<Shape>,
+    // Rectangle of cells no word may use. The grid is not compacted while one is set
+    pub reserved: Option<Region>,
+    pub orientation: Orientation,
+    // Swap rows and columns after orienting, turning horizontal words vertical and back
+    pub transpose: bool,
+    // Direction the letters of every word read in
+    pub reading_order: ReadingOrder,
+    pub preference: Preference,
+    // Start wider (or taller) than square and penalize placements that stretch the
+    // grid in the other dimension
+    pub growth_bias: Option<GrowthBias>,
+    // Reject solutions whose used region is longer than this many times its breadth
+    pub max_aspect: Option<f64>,
+    // Random positions the standard algorithm tries for each word before abandoning the attempt
+    pub placement_tries: usize,
+    // Multiplier applied to the estimated grid size before the staged schedule scales it
+    pub size_factor: f64,
+    // Most promising intersections kept for the placement algorithms to work from
+    pub max_intersections_considered: usize,
+    // Best-scoring positions kept per word when the placement algorithms list candidates
+    pub max_candidates: usize,
+    // Most positions that cross no placed letter scored per word when listing candidates,
+    // sampled evenly across the grid. Crossing positions are always scored. None scores
+    // every position
+    pub max_positions_scored: Option<usize>,
+    // Chance, from 0 to 1, that the optimized algorithm takes the best remaining candidate
+    // position for a word rather than a random one. None tries the three best first and
+    // then random ones
+    pub greediness: Option<f64>,
+    // Simulated annealing iterations run on each solution (0 disables annealing)
+    pub anneal_iterations: usize,
+    // Starting temperature, multiplied by the cooling rate every 50 iterations
+    pub anneal_initial_temperature: f64,
+    pub anneal_cooling_rate: f64,
+    // Let annealing move a word into the other direction, not just to another position
+    pub flexible_directions: bool,
+    // Keep the grid at the size it was generated at, with word positions in that frame
+    pub skip_compaction: bool,
+    // Stop an algorithm's attempts once a solution is at most this area
+    pub good_enough_area: Option<usize>,
+    // Number of distinct solutions to keep across attempts: generate returns the best
+    // and alternatives() the runners-up
+    pub top: usize,
+    // Generate reproducibly from this seed instead of a fresh random one each run
+    pub seed: Option<u64>,
+    // Blank out this fraction of each word's letters in the word bank, for a fill-in
+    // activity. The grid and answer key keep every letter
+    pub blank_bank: Option<f64>,
+}
+
+impl Default for GenerationConfig {
+    fn default() -> Self {
+        Self {
+            fixed_width: None,
+            fixed_height: None,
+            auto_grow: false,
+            max_grow: 10,
+            placement_order: PlacementOrder::Random,
+            queue_interleave: QueueInterleave::Alternate,
+            strict_separation: false,
+            valid_crossword: false,
+            forbid_nested_words: false,
+            max_intersections_per_word: None,
+            all_words_connected: false,
+            margin: 0,
+            rare_letter_bonus: false,
+            preferred_intersection_letters: BTreeSet::new(),
+            include_reversed: false,
+            spread: false,
+            algorithm: None,
+            shape: None,
+            reserved: None,
+            orientation: Orientation::Normal,
+            transpose: false,
+            reading_order: ReadingOrder::Natural,
+            preference: Preference::Balanced,
+            growth_bias: None,
+            max_aspect: None,
+            placement_tries: 150,
+            size_factor: 1.0,
+            max_intersections_considered: 10_000,
+            max_candidates: 50,
+            max_positions_scored: None,
+            greediness: None,
+            anneal_iterations: 100,
+            anneal_initial_temperature: 1000.0,
+            anneal_cooling_rate: 0.95,
+            flexible_directions: false,
+            skip_compaction: false,
+            good_enough_area: None,
+            top: 1,
+            seed: None,
+            blank_bank: None,
+        }
+    }
+}
+
+impl GenerationConfig {
+    /// Check that every setting is within its valid range, describing the first one
+    /// that is not.
+    ///
+    /// ```
+    /// use wordsearch_generator::GenerationConfig;
+    ///
+    /// assert!(GenerationConfig::default().validate().is_ok());
+    /// let config: GenerationConfig = serde_yaml::from_str("greediness: 1.5").unwrap();
+    /// assert!(config.validate().is_err());
+    /// assert!(serde_yaml::from_str::<GenerationConfig>("greedyness: 0.5").is_err());
+    /// let config: GenerationConfig = serde_yaml::from_str("size_factor: 50.0").unwrap();
+    /// assert!(config.validate().is_err());
+    /// ```
+    pub fn validate(&self) -> Result<(), String> {
+        if self.anneal_initial_temperature <= 0.0 || self.anneal_cooling_rate <= 0.0 || self.anneal_cooling_rate > 1.0 {
+            return Err("anneal_initial_temperature must be positive and anneal_cooling_rate must be in (0, 1]".to_string());
+        }
+        if self.max_candidates == 0 || self.max_positions_scored == Some(0) {
+            return Err("max_candidates and max_positions_scored must be at least 1".to_string());
+        }
+        if self.greediness.is_some_and(|greediness| !(0.0..=1.0).contains(&greediness)) {
+            return Err("greediness must be between 0 and 1".to_string());
+        }
+        if self.blank_bank.is_some_and(|ratio| !(0.0..=1.0).contains(&ratio)) {
+            return Err("blank_bank must be between 0 and 1".to_string());
+        }
+        if self.max_aspect.is_some_and(|ratio| ratio < 1.0) {
+            return Err("max_aspect must be at least 1".to_string());
+        }
+        if !(self.size_factor > 0.0 && self.size_factor <= MAX_SIZE_FACTOR) {
+            return Err(format!("size_factor must be positive and at most {}", MAX_SIZE_FACTOR));
+        }
+        if self.fixed_width == Some(0) || self.fixed_height == Some(0) {
+            return Err("fixed_width and fixed_height must be at least 1".to_string());
+        }
+        if self.top == 0 {
+            return Err("top must be at least 1".to_string());
+        }
+        if self.top > 1 && matches!(self.algorithm, Some(Algorithm::Greedy | Algorithm::Backtrack)) {
+            return Err("top above 1 needs an algorithm that makes several attempts; greedy and backtrack each stop at their first layout".to_string());
+        }
+        if let Some(region) = self.reserved {
+            if self.fixed_width.is_some_and(|width| region.right >= width) || self.fixed_height.is_some_and(|height| region.bottom >= height) {
+                return Err("the reserved region extends past the fixed_width/fixed_height grid".to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+pub struct WordSearchGenerator {
+    horizontal_words: Vec<String>,
+    vertical_words: Vec<String>,
+    // Original form of every word that had spaces stripped for placement
+    display_forms: HashMap<String, String>,
+    // Input words, as given, that had leading or trailing whitespace removed
+    trimmed_words: Vec<String>,
+    // Group label of each grouped word, keyed by the word as placed
+    word_groups: HashMap<String, String>,
+    // Words exactly as given in the input, horizontal then vertical
+    input_words: Vec<String>,
+    pub silent: bool,
+    // Hide the periodic "attempt X/Y" lines while keeping other progress messages
+    pub quiet_progress: bool,
+    // Tuning and layout settings, see GenerationConfig
+    config: GenerationConfig,
+    // Mean number of occurrences of each distinct letter across both lists
+    average_letter_frequency: f64,
+    // Pairs of words that must never cross each other
+    pub forbidden_crossings: Vec<(String, String)>,
+    // Pairs of words that must cross each other
+    pub required_crossings: Vec<(String, String)>,
+    // Words at fixed positions, placed before any other word and never moved
+    pinned: Vec<PlacedWord>,
+    // Random state left by the last generate run, used to shuffle the word bank so it
+    // follows the same seed. Generation itself takes its rng as a parameter
+    rng: RefCell<StdRng>,
+    // Picks the word bank blanks when no seed is set
+    bank_seed: u64,
+    on_improvement: RefCell<Option<ImprovementCallback>>,
+    // Attempts run by the algorithm currently in progress, for its stage timing
+    attempts_used: Cell<usize>,
+    stats: RefCell<GenerationStats>,
+    // Best distinct attempt solutions with their scores, best first and at most
+    // GenerationConfig::top of them, while generating
+    runners_up: RefCell<Vec<(f64, Grid, Vec<PlacedWord>)>>,
+    // Post-processed runners-up of the last generate run, excluding the returned solution
+    alternatives: RefCell<Vec<(Grid, Vec<PlacedWord>)>>,
+}
+
+impl WordSearchGenerator {
+    /// Set up a generator for the word lists: a shared `words` list is split between the
+    /// two directions, words are trimmed, and spaces and hyphens are left out of the grid
+    /// while the word bank and answer key keep the form given. With `silent` set no
+    /// progress is printed.
+    ///
+    /// # Panics
+    ///
+    /// If a word is empty or only whitespace, with the message from
+    /// [`WordLists::validate`]. Use [`WordSearchGenerator::try_new`] to get it as an error.
+    pub fn new(word_lists: WordLists, silent: bool) -> Self {
+        Self::try_new(word_lists, silent).unwrap_or_else(|message| panic!("{}", message))
+    }
+
+    /// Set up a generator as [`WordSearchGenerator::new`] does, or return the error from
+    /// [`WordLists::validate`] if a word is empty or only whitespace.
+    ///
+    /// ```
+    /// use wordsearch_generator::{WordLists, WordSearchGenerator};
+    ///
+    /// let words = WordLists::new(vec!["CAT".to_string(), "".to_string()], vec!["TOP".to_string()]);
+    /// assert_eq!(WordSearchGenerator::try_new(words, true).err().as_deref(),
+    ///            Some("empty words in the word lists: horizontal #2 \"\""));
+    ///
+    /// let words = WordLists::new(vec!["CAT".to_string()], vec!["TOP".to_string()]);
+    /// assert!(WordSearchGenerator::try_new(words, true).is_ok());
+    /// ```
+    pub fn try_new(mut word_lists: WordLists, silent: bool) -> Result<Self, String> {
+        word_lists.validate()?;
+        word_lists.split_words();
+
+        // Stray whitespace around a word (e.g. " CAT ") is never part of it
+        let mut trimmed_words = Vec::new();
+        let mut trim = |word: String| -> String {
+            let trimmed = word.trim().to_string();
+            if trimmed != word {
+                trimmed_words.push(word);
+            }
+            trimmed
+        };
+        let word_lists = WordLists {
+            horizontal: word_lists.horizontal.into_iter().map(&mut trim).collect(),
+            vertical: word_lists.vertical.into_iter().map(&mut trim).collect(),
+            pinned: word_lists.pinned.into_iter().map(|(word, pin)| (trim(word), pin)).collect(),
+            forbidden_crossings: word_lists.forbidden_crossings.into_iter()
+                .map(|(first, second)| (first.trim().to_string(), second.trim().to_string()))
+                .collect(),
+            required_crossings: word_lists.required_crossings.into_iter()
+                .map(|(first, second)| (first.trim().to_string(), second.trim().to_string()))
+                .collect(),
+            ..word_lists
+        };
+        let word_groups = word_lists.groups.iter()
+            .flat_map(|(label, words)| words.iter().map(move |word| (strip_separators(word), label.clone())))
+            .collect();
+
+        // Pinned words are listed after the others, alphabetically, unless already in a list
+        let mut pinned_words: Vec<&String> = word_lists.pinned.keys().collect();
+        pinned_words.sort();
+        let mut input_words: Vec<String> = word_lists.horizontal.iter().chain(&word_lists.vertical).cloned().collect();
+        for word in pinned_words {
+            if !input_words.contains(word) {
+                input_words.push(word.clone());
+            }
+        }
+
+        // Phrases are placed without their spaces but keep their original form for display
+        let mut display_forms = HashMap::new();
+        let mut strip_phrases = |words: Vec<String>| -> Vec<String> {
+            words.into_iter()
+                .map(|word| {
+                    let stripped = strip_separators(&word);
+                    if stripped != word {
+                        display_forms.insert(stripped.clone(), word);
+                    }
+                    stripped
+                })
+                .collect()
+        };
+
+        // Pinned words take their position from the pin, so they leave the lists
+        let mut pinned: Vec<PlacedWord> = word_lists.pinned.iter()
+            .map(|(word, pin)| PlacedWord::new(strip_phrases(vec![word.clone()]).remove(0), pin.row, pin.col, pin.direction))
+            .collect();
+        pinned.sort_by(|a, b| a.word.cmp(&b.word));
+        let unpinned = |words: Vec<String>| -> Vec<String> {
+            words.into_iter().filter(|word| !word_lists.pinned.contains_key(word)).collect()
+        };
+
+        // Sort words by length (descending) to place longer words first
+        let mut horizontal_words = strip_phrases(unpinned(word_lists.horizontal));
+        let mut vertical_words = strip_phrases(unpinned(word_lists.vertical));
+        let forbidden_crossings = word_lists.forbidden_crossings.iter()
+            .map(|(first, second)| (strip_separators(first), strip_separators(second)))
+            .collect();
+        let required_crossings = word_lists.required_crossings.iter()
+            .map(|(first, second)| (strip_separators(first), strip_separators(second)))
+            .collect();
+        horizontal_words.sort_by_key(|w| std::cmp::Reverse(w.chars().count()));
+        vertical_words.sort_by_key(|w| std::cmp::Reverse(w.chars().count()));
+
+        let mut distinct_letters: Vec<char> = horizontal_words.iter().chain(&vertical_words).flat_map(|w| w.chars()).collect();
+        let total_letters = distinct_letters.len();
+        distinct_letters.sort_unstable();
+        distinct_letters.dedup();
+        let average_letter_frequency = total_letters as f64 / distinct_letters.len().max(1) as f64;
+        
+        Ok(Self {
+            horizontal_words,
+            vertical_words,
+            display_forms,
+            trimmed_words,
+            word_groups,
+            input_words,
+            silent,
+            quiet_progress: false,
+            config: GenerationConfig::default(),
+            average_letter_frequency,
+            forbidden_crossings,
+            required_crossings,
+            pinned,
+            rng: RefCell::new(StdRng::from_entropy()),
+            bank_seed: rand::random(),
+            on_improvement: RefCell::new(None),
+            attempts_used: Cell::new(0),
+            stats: RefCell::new(GenerationStats::default()),
+            runners_up: RefCell::new(Vec::new()),
+            alternatives: RefCell::new(Vec::new()),
+        })
+    }
+
+    // The generation settings in use
+    pub fn config(&self) -> &GenerationConfig {
+        &self.config
+    }
+
+    /// Replace the generation settings, e.g. with ones loaded from a file. Switching to
+    /// `PlacementOrder::Intersections` reorders the words here.
+    ///
+    /// ```
+    /// use wordsearch_generator::{GenerationConfig, WordLists, WordSearchGenerator};
+    ///
+    /// let recipe = "seed: 7\nplacement_order: intersections\nanneal_iterations: 20\n";
+    /// let config: GenerationConfig = serde_yaml::from_str(recipe).unwrap();
+    /// assert_eq!(config.max_candidates, GenerationConfig::default().max_candidates);
+    ///
+    /// let words = || WordLists::new(vec!["THREE".to_string(), "ONE".to_string()], vec!["TEN".to_string(), "EIGHT".to_string()]);
+    /// let mut first = WordSearchGenerator::new(words(), true);
+    /// first.set_config(config.clone());
+    /// let mut second = WordSearchGenerator::new(words(), true);
+    /// second.set_config(config);
+    /// assert_eq!(first.generate(20).unwrap().0.cells, second.generate(20).unwrap().0.cells);
+    /// ```
+    pub fn set_config(&mut self, config: GenerationConfig) {
+        let reorder = config.placement_order == PlacementOrder::Intersections
+            && self.config.placement_order != PlacementOrder::Intersections;
+        self.config = config;
+        if !reorder {
+            return;
+        }
+
+        // Total intersection potential of each word - highly connective words are
+        // placed first as they are the hardest to satisfy once the grid fills up
+        let mut h_potential = vec![0.0; self.horizontal_words.len()];
+        let mut v_potential = vec![0.0; self.vertical_words.len()];
+        self.for_each_intersection(|intersection| {
+            let score = self.score_intersection_potential(&intersection);
+            h_potential[intersection.h_word_idx] += score;
+            v_potential[intersection.v_word_idx] += score;
+        });
+
+        self.horizontal_words = Self::sort_by_potential(&self.horizontal_words, &h_potential);
+        self.vertical_words = Self::sort_by_potential(&self.vertical_words, &v_potential);
+    }
+
+    fn sort_by_potential(words: &[String], potential: &[f64]) -> Vec<String> {
+        let mut order: Vec<usize> = (0..words.len()).collect();
+        order.sort_by(|&a, &b| potential[b].partial_cmp(&potential[a]).unwrap_or(std::cmp::Ordering::Equal));
+        order.into_iter().map(|i| words[i].clone()).collect()
+    }
+
+    // Generate as normal, calling on_improvement with each new best solution as
+    // the optimized, intersection-first and annealing stages discover them
+    pub fn generate_streaming(&self, max_attempts: usize, on_improvement: impl FnMut(&SolutionUpdate) + 'static) -> Option<(Grid, Vec<PlacedWord>)> {
+        *self.on_improvement.borrow_mut() = Some(Box::new(on_improvement));
+        let result = self.generate(max_attempts);
+        *self.on_improvement.borrow_mut() = None;
+        result
+    }
+
+    fn report_improvement(&self, stage: &str, grid: &Grid, placed_words: &[PlacedWord], score: f64) {
+        if let Some(callback) = self.on_improvement.borrow_mut().as_mut() {
+            callback(&SolutionUpdate { stage, grid, placed_words, score });
+        }
+    }
+
+    // Create an empty grid carrying the placement constraints of this generator
+    fn empty_grid(&self, width: usize, height: usize) -> Grid {
+        let mut grid = Grid::new(width, height);
+        grid.strict_separation = self.config.strict_separation;
+        grid.valid_crossword = self.config.valid_crossword;
+        grid.mask = self.grid_mask(width, height);
+        grid.keep_origin = !self.pinned.is_empty() || self.config.reserved.is_some();
+        grid
+    }
+
+    // Cells words may use in a grid of this size: inside the shape and outside the
+    // reserved region. None when there is neither, leaving every cell open
+    fn grid_mask(&self, width: usize, height: usize) -> Option<Vec<Vec<bool>>> {
+        if self.config.shape.is_none() && self.config.reserved.is_none() {
+            return None;
+        }
+        let mut mask = match self.config.shape {
+            Some(shape) => shape.mask(width, height),
+            None => vec![vec![true; width]; height],
+        };
+        if let Some(region) = self.config.reserved {
+            for (row, cells) in mask.iter_mut().enumerate() {
+                for (col, open) in cells.iter_mut().enumerate() {
+                    if region.contains(row, col) {
+                        *open = false;
+                    }
+                }
+            }
+        }
+        if self.half_turned() {
+            mask.reverse();
+            mask.iter_mut().for_each(|row| row.reverse());
+        }
+        Some(mask)
+    }
+
+    // Whether words are laid out in a grid turned half a turn from the finished one, which
+    // post_process turns back so that every word reads backwards. The shape, reserved
+    // region and pins are turned too, so they end up where they were asked to be
+    fn half_turned(&self) -> bool {
+        self.config.reading_order == ReadingOrder::Reversed
+    }
+
+    // The pinned words as laid out in a grid of this size: at their pins, or in a
+    // half-turned grid where the turn back brings them to their pins. None if one
+    // does not fit
+    fn pins_at_size(&self, width: usize, height: usize) -> Option<Vec<PlacedWord>> {
+        if !self.half_turned() {
+            return Some(self.pinned.clone());
+        }
+        self.pinned.iter()
+            .map(|word| {
+                let (end_row, end_col) = word.end_position();
+                Some(PlacedWord {
+                    start_row: height.checked_sub(end_row + 1)?,
+                    start_col: width.checked_sub(end_col + 1)?,
+                    ..word.clone()
+                })
+            })
+            .collect()
+    }
+
+    // Empty grid holding just the pinned words, the starting point of every attempt.
+    // None if a pinned word does not fit at this size
+    fn pinned_layout(&self, width: usize, height: usize) -> Option<(Grid, Vec<PlacedWord>)> {
+        let mut grid = self.empty_grid(width, height);
+        let pinned = self.pins_at_size(width, height)?;
+        for word in &pinned {
+            let (end_row, end_col) = word.end_position();
+            if !grid.place_word(&word.word, end_row, end_col, word.direction) {
+                return None;
+            }
+        }
+        Some((grid, pinned))
+    }
+
+    fn is_pinned(&self, word: &PlacedWord) -> bool {
+        self.pinned.iter().any(|pinned| pinned.word == word.word && pinned.direction == word.direction)
+    }
+
+    /// Pinned words that cannot be placed where pinned, because they run off the grid,
+    /// clash with the letters of an earlier pinned word (alphabetically), fall outside the
+    /// shape or inside the reserved region, or break the separation and crossing rules.
+    /// Generation cannot succeed while this is non-empty.
+    ///
+    /// ```
+    /// use wordsearch_generator::{Direction, Pin, WordLists, WordSearchGenerator};
+    ///
+    /// let mut words = WordLists::new(vec!["OWL".to_string()], vec!["BAT".to_string()]);
+    /// words.pinned.insert("CAT".to_string(), Pin { row: 0, col: 0, direction: Direction::Horizontal });
+    /// words.pinned.insert("DOG".to_string(), Pin { row: 0, col: 1, direction: Direction::Vertical });
+    /// let generator = WordSearchGenerator::new(words, true);
+    ///
+    /// // DOG's D would land on CAT's A
+    /// assert_eq!(generator.pin_conflicts(), vec!["DOG"]);
+    /// ```
+    pub fn pin_conflicts(&self) -> Vec<&str> {
+        // Checked at the size generation starts at, grown to reach every pin, so that pinned
+        // words may not run outside the shape or into the reserved region
+        let (start_width, start_height) = self.base_grid_size();
+        let width = self.pinned.iter().map(|word| word.end_position().1 + 1)
+            .fold(self.config.fixed_width.unwrap_or(start_width), usize::max);
+        let height = self.pinned.iter().map(|word| word.end_position().0 + 1)
+            .fold(self.config.fixed_height.unwrap_or(start_height), usize::max);
+        let mut grid = self.empty_grid(width, height);
+
+        let mut placed_words = Vec::new();
+        let mut conflicts = Vec::new();
+        // The grid reaches every pin, so each fits once turned
+        let pinned = self.pins_at_size(width, height).unwrap_or_default();
+        for (word, pin) in pinned.iter().zip(&self.pinned) {
+            let (end_row, end_col) = word.end_position();
+            let mut trial_words = placed_words.clone();
+            trial_words.push(word.clone());
+            if self.is_allowed_placement(&grid, &word.word, end_row, end_col, word.direction) && self.is_acceptable(&trial_words) {
+                grid.place_word(&word.word, end_row, end_col, word.direction);
+                placed_words = trial_words;
+            } else {
+                conflicts.push(pin.word.as_str());
+            }
+        }
+        conflicts
+    }
+
+    // Call `visit` with every letter pair shared by a horizontal and a vertical word,
+    // one at a time so callers that only tally them never hold the whole list
+    fn for_each_intersection(&self, mut visit: impl FnMut(Intersection)) {
+        for (h_idx, h_word) in self.horizontal_words.iter().enumerate() {
+            for (v_idx, v_word) in self.vertical_words.iter().enumerate() {
+                if self.is_forbidden_crossing(h_word, v_word) {
+                    continue;
+                }
+                let h_chars: Vec<char> = h_word.chars().collect();
+                let v_chars: Vec<char> = v_word.chars().collect();
+                
+                for (h_char_idx, &h_char) in h_chars.iter().enumerate() {
+                    for (v_char_idx, &v_char) in v_chars.iter().enumerate() {
+                        if h_char == v_char {
+                            visit(Intersection {
+                                h_word_idx: h_idx,
+                                v_word_idx: v_idx,
+                                h_char_idx,
+                                v_char_idx,
+                                character: h_char,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // The `limit` most promising intersections, best first. Only the current top
+    // `limit` are held at once, in a min-heap whose weakest entry is evicted when a
+    // better one turns up, so huge word lists never materialize every letter pair
+    fn find_top_intersections(&self, limit: usize) -> Vec<Intersection> {
+        let mut heap: BinaryHeap<std::cmp::Reverse<RankedIntersection>> = BinaryHeap::new();
+        let mut order = 0;
+        
+        self.for_each_intersection(|intersection| {
+            let ranked = RankedIntersection {
+                score: self.score_intersection_potential(&intersection),
+                order,
+                intersection,
+            };
+            order += 1;
+            if heap.len() < limit {
+                heap.push(std::cmp::Reverse(ranked));
+            } else if heap.peek().is_some_and(|std::cmp::Reverse(weakest)| ranked > *weakest) {
+                heap.pop();
+                heap.push(std::cmp::Reverse(ranked));
+            }
+        });
+        
+        // Best potential first (prefer common letters, center positions)
+        heap.into_sorted_vec().into_iter().map(|std::cmp::Reverse(ranked)| ranked.intersection).collect()
+    }
+
+    // True when all words run in one direction, so the puzzle is just stacked words
+    fn is_single_direction(&self) -> bool {
+        self.horizontal_words.is_empty() || self.vertical_words.is_empty()
+    }
+
+    /// Pairs `(shorter, longer)` of words in the same direction where the longer word
+    /// contains the shorter one. With `forbid_nested_words` the shorter word may not be
+    /// placed on the longer one's letters, so it needs a spot of its own.
+    ///
+    /// ```
+    /// use wordsearch_generator::{GenerationConfig, WordLists, WordSearchGenerator};
+    ///
+    /// let words = WordLists::new(
+    ///     vec!["CAT".to_string(), "CATALOG".to_string(), "DOG".to_string()],
+    ///     vec!["LOG".to_string()],
+    /// );
+    /// let mut generator = WordSearchGenerator::new(words, true);
+    /// assert_eq!(generator.substring_words(), vec![(&"CAT".to_string(), &"CATALOG".to_string())]);
+    ///
+    /// generator.set_config(GenerationConfig { forbid_nested_words: true, ..GenerationConfig::default() });
+    /// let (grid, placed_words) = generator.generate(50).expect("words should fit");
+    /// let cells = |word: &str| placed_words.iter().find(|placed| placed.word == word).unwrap().cells().collect::<Vec<_>>();
+    /// assert!(!cells("CAT").iter().all(|cell| cells("CATALOG").contains(cell)));
+    /// # assert!(grid.misplaced_words(&placed_words).is_empty());
+    /// ```
+    pub fn substring_words(&self) -> Vec<(&String, &String)> {
+        let mut pairs = Vec::new();
+        for words in [&self.horizontal_words, &self.vertical_words] {
+            for shorter in words.iter() {
+                for longer in words.iter() {
+                    if shorter.chars().count() < longer.chars().count() && longer.contains(shorter.as_str()) {
+                        pairs.push((shorter, longer));
+                    }
+                }
+            }
+        }
+        pairs
+    }
+
+    // Words that cannot intersect any word of the opposite direction
+    pub fn disconnected_words(&self) -> Vec<&String> {
+        // With a single direction nothing can cross, so no word is singled out
+        if self.is_single_direction() {
+            return Vec::new();
+        }
+
+        let mut h_connected = vec![false; self.horizontal_words.len()];
+        let mut v_connected = vec![false; self.vertical_words.len()];
+        self.for_each_intersection(|intersection| {
+            h_connected[intersection.h_word_idx] = true;
+            v_connected[intersection.v_word_idx] = true;
+        });
+
+        let h_disconnected = self.horizontal_words.iter().zip(h_connected).filter(|(_, connected)| !connected);
+        let v_disconnected = self.vertical_words.iter().zip(v_connected).filter(|(_, connected)| !connected);
+        h_disconnected.chain(v_disconnected).map(|(word, _)| word).collect()
+    }
+
+    /// Words, exactly as given in the input, whose leading or trailing whitespace was
+    /// removed. The trimmed word is what gets placed and listed.
+    ///
+    /// ```
+    /// use wordsearch_generator::{WordLists, WordSearchGenerator};
+    ///
+    /// let words = WordLists::new(vec![" CAT ".to_string()], vec!["TOP".to_string()]);
+    /// let generator = WordSearchGenerator::new(words, true);
+    /// assert_eq!(generator.trimmed_words(), [" CAT "]);
+    ///
+    /// let (grid, placed_words) = generator.generate(50).expect("words should fit");
+    /// let cat = placed_words.iter().find(|word| word.word == "CAT").expect("CAT should be placed");
+    /// assert_eq!(cat.display_word(), "CAT");
+    /// // CAT and TOP cross on their T
+    /// assert_eq!(grid.get_used_dimensions(), (3, 3));
+    /// ```
+    pub fn trimmed_words(&self) -> &[String] {
+        &self.trimmed_words
+    }
+
+    // Words from either list that do not appear in placed_words
+    pub fn missing_words(&self, placed_words: &[PlacedWord]) -> Vec<&String> {
+        let placed: HashSet<&str> = placed_words.iter().map(|word| word.word.as_str()).collect();
+        self.horizontal_words.iter().chain(&self.vertical_words)
+            .filter(|word| !placed.contains(word.as_str()))
+            .collect()
+    }
+
+    // Pairs of words, from either list, within max_distance edits of each other.
+    // These are often typos ("ELEPHENT") or make the puzzle too easy ("CAT" and "CATS")
+    pub fn similar_words(&self, max_distance: usize) -> Vec<(&String, &String, usize)> {
+        let words: Vec<&String> = self.horizontal_words.iter().chain(&self.vertical_words).collect();
+        let mut similar = Vec::new();
+        for (i, &a) in words.iter().enumerate() {
+            for &b in &words[i + 1..] {
+                // The distance is at least the difference in length, so skip the full computation
+                if a.chars().count().abs_diff(b.chars().count()) > max_distance {
+                    continue;
+                }
+                let distance = edit_distance(a, b);
+                if distance <= max_distance {
+                    similar.push((a, b, distance));
+                }
+            }
+        }
+        similar
+    }
+
+    fn score_intersection_potential(&self, intersection: &Intersection) -> f64 {
+        let mut score = 0.0;
+        
+        // Prefer intersections with longer words
+        let h_word_len = self.horizontal_words[intersection.h_word_idx].chars().count() as f64;
+        let v_word_len = self.vertical_words[intersection.v_word_idx].chars().count() as f64;
+        score += (h_word_len + v_word_len) * 2.0;
+        
+        // Prefer intersections closer to word centers
+        let h_center_distance = (intersection.h_char_idx as f64 - h_word_len / 2.0).abs();
+        let v_center_distance = (intersection.v_char_idx as f64 - v_word_len / 2.0).abs();
+        score += 20.0 - (h_center_distance + v_center_distance);
+        
+        // Prefer common letters that appear in multiple words
+        let letter_frequency = self.count_letter_frequency(intersection.character);
+        score += letter_frequency as f64 * 5.0;
+        
+        score
+    }
+
+    /// Letter frequencies and intersection potential of the word lists, without generating
+    pub fn analyze(&self) -> WordListAnalysis {
+        let mut letters: Vec<char> = self.horizontal_words.iter()
+            .chain(self.vertical_words.iter())
+            .flat_map(|word| word.chars())
+            .collect();
+        letters.sort_unstable();
+        letters.dedup();
+
+        let count_in = |words: &[String], letter: char| -> usize {
+            words.iter().map(|word| word.chars().filter(|&c| c == letter).count()).sum()
+        };
+        let total_letters: usize = self.horizontal_words.iter()
+            .chain(self.vertical_words.iter())
+            .map(|word| word.chars().count())
+            .sum();
+
+        let mut frequencies: Vec<LetterFrequency> = letters.into_iter()
+            .map(|letter| {
+                let horizontal = count_in(&self.horizontal_words, letter);
+                let vertical = count_in(&self.vertical_words, letter);
+                let count = self.count_letter_frequency(letter);
+                LetterFrequency {
+                    letter,
+                    count,
+                    horizontal,
+                    vertical,
+                    // Letters missing from one direction can never be crossed, and very
+                    // scarce letters rarely line up with a partner
+                    rare: horizontal == 0 || vertical == 0 || count * 50 < total_letters,
+                }
+            })
+            .collect();
+        frequencies.sort_by(|a, b| b.count.cmp(&a.count).then(a.letter.cmp(&b.letter)));
+
+        let mut possible_intersections = 0;
+        self.for_each_intersection(|_| possible_intersections += 1);
+
+        WordListAnalysis {
+            letters: frequencies,
+            total_letters,
+            possible_intersections,
+            disconnected_words: self.disconnected_words().into_iter().cloned().collect(),
+        }
+    }
+
+    // Weight of an intersection on the given letter: 1.0 normally, or with
+    // rare_letter_bonus the average letter frequency divided by this letter's
+    // frequency, so crossings on scarce letters score higher than on common ones.
+    // Crossings on a preferred intersection letter are multiplied by
+    // PREFERRED_LETTER_BOOST on top of that
+    fn intersection_letter_weight(&self, letter: char) -> f64 {
+        let mut weight = 1.0;
+        if self.config.rare_letter_bonus {
+            let frequency = self.count_letter_frequency(letter).max(1) as f64;
+            weight = self.average_letter_frequency / frequency;
+        }
+        if letter.to_lowercase().any(|ch| self.config.preferred_intersection_letters.contains(&ch)) {
+            weight *= PREFERRED_LETTER_BOOST;
+        }
+        weight
+    }
+
+    fn count_letter_frequency(&self, letter: char) -> usize {
+        let mut count = 0;
+        for word in &self.horizontal_words {
+            count += word.chars().filter(|&c| c == letter).count();
+        }
+        for word in &self.vertical_words {
+            count += word.chars().filter(|&c| c == letter).count();
+        }
+        count
+    }
+
+    fn calculate_placement_score(&self, grid: &Grid, placed_words: &[PlacedWord], word: &str, row: usize, col: usize,
+                                direction: Direction) -> f64 {
+        let mut score = 0.0;
+        
+        // Base score - prefer central placements
+        let center_row = grid.height as f64 / 2.0;
+        let center_col = grid.width as f64 / 2.0;
+        let distance_from_center = ((row as f64 - center_row).powi(2) + 
+                                   (col as f64 - center_col).powi(2)).sqrt();
+        if self.config.spread {
+            // Spread words out instead: reward distance from the nearest placed word,
+            // or from the center (i.e. favor the edges) while the grid is empty
+            let half_len = word.chars().count().saturating_sub(1) as f64 / 2.0;
+            let (mid_row, mid_col) = match direction {
+                Direction::Horizontal => (row as f64, col as f64 - half_len),
+                Direction::Vertical => (row as f64 - half_len, col as f64),
+            };
+            let nearest_word = placed_words.iter()
+                .map(|placed| {
+                    let half_len = placed.word.chars().count().saturating_sub(1) as f64 / 2.0;
+                    let (placed_row, placed_col) = match placed.direction {
+                        Direction::Horizontal => (placed.start_row as f64, placed.start_col as f64 + half_len),
+                        Direction::Vertical => (placed.start_row as f64 + half_len, placed.start_col as f64),
+                    };
+                    ((mid_row - placed_row).powi(2) + (mid_col - placed_col).powi(2)).sqrt()
+                })
+                .reduce(f64::min);
+            score += nearest_word.unwrap_or(distance_from_center) * 10.0;
+        } else {
+            score += 100.0 - distance_from_center;
+        }
+        
+        // Heavily reward intersections
+        let mut intersection_weight = 0.0;
+        let chars: Vec<char> = word.chars().collect();
+        
+        match direction {
+            Direction::Horizontal => {
+                let start_col = col + 1 - chars.len();
+                for (i, &ch) in chars.iter().enumerate() {
+                    let c = start_col + i;
+                    if let Some(existing) = grid.cells[row][c] {
+                        if existing == ch {
+                            intersection_weight += self.intersection_letter_weight(ch);
+                        }
+                    }
+                }
+            }
+            Direction::Vertical => {
+                let start_row = row + 1 - chars.len();
+                for (i, &ch) in chars.iter().enumerate() {
+                    let r = start_row + i;
+                    if let Some(existing) = grid.cells[r][col] {
+                        if existing == ch {
+                            intersection_weight += self.intersection_letter_weight(ch);
+                        }
+                    }
+                }
+            }
+        }
+        
+        // Large bonus for each intersection
+        score += intersection_weight * 50.0;
+
+        // Bonus for word length (longer words get priority)
+        score += word.chars().count() as f64 * 2.0;
+        
+        // Bonus for creating more future intersection opportunities
+        score += intersection_weight * 25.0;
+        
+        score
+    }
+
+    fn estimate_grid_size(&self) -> (usize, usize) {
+        let max_h_len = self.horizontal_words.iter().map(|w| w.chars().count()).max().unwrap_or(0);
+        let max_v_len = self.vertical_words.iter().map(|w| w.chars().count()).max().unwrap_or(0);
+        
+        // More conservative estimation - account for potential intersections
+        let h_chars: usize = self.horizontal_words.iter().map(|w| w.chars().count()).sum();
+        let v_chars: usize = self.vertical_words.iter().map(|w| w.chars().count()).sum();
+        
+        // Assume 10-20% overlap from intersections, unless there is nothing to cross
+        let total_chars = h_chars + v_chars;
+        let overlap_factor = if self.is_single_direction() { 1.0 } else { 0.85 }; // Expect 15% reduction from intersections
+        // The reserved region takes space the words cannot use
+        let reserved_area = self.config.reserved.map_or(0, |region| region.area());
+        let estimated_area = (total_chars as f64 * overlap_factor) as usize + reserved_area;
+        let estimated_side = (estimated_area as f64).sqrt() as usize;
+        
+        // Ensure grid can fit the longest words and reach every pinned word and the reserved region
+        let pinned_width = self.pinned.iter().map(|word| word.end_position().1 + 1)
+            .chain(self.config.reserved.map(|region| region.right + 1))
+            .max().unwrap_or(0);
+        let pinned_height = self.pinned.iter().map(|word| word.end_position().0 + 1)
+            .chain(self.config.reserved.map(|region| region.bottom + 1))
+            .max().unwrap_or(0);
+        let min_width = max_h_len.max(self.vertical_words.len()).max(pinned_width).max(10);
+        let min_height = max_v_len.max(self.horizontal_words.len()).max(pinned_height).max(10);
+        
+        let width = estimated_side.max(min_width);
+        let height = estimated_side.max(min_height);
+
+        // A growth bias stretches the grid towards the preferred aspect, keeping its area,
+        // but never below the longest word or the pinned words on the shrinking side
+        let stretch = GROWTH_BIAS_ASPECT.sqrt();
+        match self.config.growth_bias {
+            Some(GrowthBias::Wide) => ((width as f64 * stretch).round() as usize,
+                                       ((height as f64 / stretch).round() as usize).max(max_v_len).max(pinned_height)),
+            Some(GrowthBias::Tall) => (((width as f64 / stretch).round() as usize).max(max_h_len).max(pinned_width),
+                                       (height as f64 * stretch).round() as usize),
+            None => (width, height),
+        }
+    }
+
+    // Estimated grid size scaled by the user's size factor. A shape is drawn on a
+    // square large enough for its open cells to cover the estimated area. A small factor
+    // never takes a side below the longest word running along it
+    fn base_grid_size(&self) -> (usize, usize) {
+        let (width, height) = self.estimate_grid_size();
+        let (width, height) = match self.config.shape {
+            Some(shape) => {
+                let side = (width.max(height) as f64 / shape.fill_fraction().sqrt()).ceil() as usize;
+                (side, side)
+            }
+            None => (width, height),
+        };
+        let longest = |words: &[String]| words.iter().map(|word| word.chars().count()).max().unwrap_or(0).max(1);
+        (((width as f64 * self.config.size_factor) as usize).max(longest(&self.horizontal_words)),
+         ((height as f64 * self.config.size_factor) as usize).max(longest(&self.vertical_words)))
+    }
+
+
+    // Placement check combining the grid's own rules with generator-level limits
+    fn is_allowed_placement(&self, grid: &Grid, word: &str, row: usize, col: usize, direction: Direction) -> bool {
+        if !grid.can_place_word(word, row, col, direction) {
+            return false;
+        }
+        if let Some(max_intersections) = self.config.max_intersections_per_word {
+            if grid.count_shared_letters(word, row, col, direction) > max_intersections {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn is_forbidden_crossing(&self, a: &str, b: &str) -> bool {
+        self.forbidden_crossings.iter()
+            .any(|(first, second)| (first == a && second == b) || (first == b && second == a))
+    }
+
+    // True if the placement would share a cell with a word it is forbidden to cross
+    fn crosses_forbidden_word(&self, owners: &HashMap<(usize, usize), Vec<usize>>, placed_words: &[PlacedWord],
+                              word: &str, row: usize, col: usize, direction: Direction) -> bool {
+        placement_cells(word.chars().count(), row, col, direction)
+            .filter_map(|cell| owners.get(&cell))
+            .flatten()
+            .any(|&owner| self.is_forbidden_crossing(word, &placed_words[owner].word))
+    }
+
+    // True if, with forbid_nested_words, the placement would lie entirely on the cells of
+    // a placed word running the same way or cover every cell of one
+    fn nests_with_placed_word(&self, owners: &HashMap<(usize, usize), Vec<usize>>, placed_words: &[PlacedWord],
+                              word: &str, row: usize, col: usize, direction: Direction) -> bool {
+        if !self.config.forbid_nested_words {
+            return false;
+        }
+        let len = word.chars().count();
+        let mut shared_cells: HashMap<usize, usize> = HashMap::new();
+        for cell in placement_cells(len, row, col, direction) {
+            for &owner in owners.get(&cell).into_iter().flatten() {
+                if placed_words[owner].direction == direction {
+                    *shared_cells.entry(owner).or_default() += 1;
+                }
+            }
+        }
+        shared_cells.into_iter().any(|(owner, shared)| shared == len || shared == placed_words[owner].word.chars().count())
+    }
+
+    // Final check applied to every complete solution before it is scored
+    fn is_acceptable(&self, placed_words: &[PlacedWord]) -> bool {
+        if let Some(max_aspect) = self.config.max_aspect {
+            let aspect = aspect_ratio(placed_words);
+            if aspect > max_aspect {
+                let mut stats = self.stats.borrow_mut();
+                if stats.best_rejected_aspect.is_none_or(|best| aspect < best) {
+                    stats.best_rejected_aspect = Some(aspect);
+                }
+                return false;
+            }
+        }
+        if let Some(max_intersections) = self.config.max_intersections_per_word {
+            if crossings_per_word(placed_words).into_iter().any(|count| count > max_intersections) {
+                return false;
+            }
+        }
+        if self.config.all_words_connected {
+            let floating: Vec<String> = placed_words.iter()
+                .filter(|word| word_crossings(word, placed_words) == 0)
+                .map(|word| word.display_word().to_string())
+                .collect();
+            if !floating.is_empty() {
+                let mut stats = self.stats.borrow_mut();
+                if stats.floating_words.is_empty() || floating.len() < stats.floating_words.len() {
+                    stats.floating_words = floating;
+                }
+                return false;
+            }
+        }
+        if self.config.valid_crossword && has_stray_run(placed_words) {
+            return false;
+        }
+        if self.config.forbid_nested_words && !nested_words(placed_words).is_empty() {
+            return false;
+        }
+        if !self.forbidden_crossings.is_empty() {
+            let crosses_forbidden = cell_owners(placed_words).values().any(|owners| {
+                owners.iter().enumerate().any(|(i, &a)| {
+                    owners[i + 1..].iter().any(|&b| self.is_forbidden_crossing(&placed_words[a].word, &placed_words[b].word))
+                })
+            });
+            if crosses_forbidden {
+                return false;
+            }
+        }
+        if self.required_crossings.iter().any(|(first, second)| {
+            matches!((self.find_placed(placed_words, first), self.find_placed(placed_words, second)),
+                     (Some(a), Some(b)) if !a.cells().any(|cell| b.cells().any(|other| other == cell)))
+        }) {
+            return false;
+        }
+        true
+    }
+
+    // The placed (not mirrored) copy of a word, if it has been placed
+    fn find_placed<'a>(&self, placed_words: &'a [PlacedWord], word: &str) -> Option<&'a PlacedWord> {
+        placed_words.iter().find(|placed| !placed.mirrored && placed.word == word)
+    }
+
+    // True if the placement would leave the word apart from an already placed word it
+    // is required to cross
+    fn misses_required_crossing(&self, placed_words: &[PlacedWord], word: &str, row: usize, col: usize, direction: Direction) -> bool {
+        self.required_crossings.iter()
+            .filter_map(|(first, second)| match (first == word, second == word) {
+                (true, _) => Some(second),
+                (_, true) => Some(first),
+                _ => None,
+            })
+            .filter_map(|partner| self.find_placed(placed_words, partner))
+            .any(|partner| !placement_cells(word.chars().count(), row, col, direction).any(|cell| partner.cells().any(|other| other == cell)))
+    }
+
+    // Start an attempt with the required crossings in place. A word whose partner is
+    // already placed (pinned, or seeded by an earlier pair) goes in across it; when
+    // neither is placed the first goes in at the start nearest the centre that leaves
+    // room for the second across it. With an rng the starts are shuffled instead, so
+    // each attempt tries a different layout. Returns the words placed from each list,
+    // by index, for the algorithm to skip; pairs that find no room are left to it
+    fn seed_required_crossings<R: Rng + ?Sized>(&self, grid: &mut Grid, placed_words: &mut Vec<PlacedWord>,
+                                                mut rng: Option<&mut R>) -> (Vec<bool>, Vec<bool>) {
+        let mut used_h_words = vec![false; self.horizontal_words.len()];
+        let mut used_v_words = vec![false; self.vertical_words.len()];
+        let list_index = |word: &str| {
+            self.horizontal_words.iter().position(|listed| listed == word).map(|idx| (Direction::Horizontal, idx))
+                .or_else(|| self.vertical_words.iter().position(|listed| listed == word).map(|idx| (Direction::Vertical, idx)))
+        };
+        for (first, second) in &self.required_crossings {
+            let placements = match (self.find_placed(placed_words, first), self.find_placed(placed_words, second)) {
+                (Some(_), Some(_)) => continue,
+                (Some(partner), None) | (None, Some(partner)) => {
+                    let word = if partner.word == *first { second } else { first };
+                    let mut crossings = self.crossing_placements(grid, placed_words, word, partner);
+                    if let Some(rng) = rng.as_deref_mut() {
+                        crossings.shuffle(rng);
+                    }
+                    crossings.into_iter().next().map(|placed| vec![placed])
+                }
+                (None, None) => {
+                    let Some((direction, _)) = list_index(first) else {
+                        continue;
+                    };
+                    let mut starts = self.open_placements(grid, first, direction);
+                    match rng.as_deref_mut() {
+                        Some(rng) => starts.shuffle(rng),
+                        None => {
+                            let center = (grid.height / 2, grid.width / 2);
+                            starts.sort_by_key(|placed| {
+                                let (row, col) = placed.cell_at(placed.word.chars().count() / 2);
+                                row.abs_diff(center.0) + col.abs_diff(center.1)
+                            });
+                        }
+                    }
+                    starts.into_iter().find_map(|placed| {
+                        let mut trial = grid.clone();
+                        let (end_row, end_col) = placed.end_position();
+                        trial.place_word(&placed.word, end_row, end_col, placed.direction);
+                        let mut crossings = self.crossing_placements(&trial, placed_words, second, &placed);
+                        if let Some(rng) = rng.as_deref_mut() {
+                            crossings.shuffle(rng);
+                        }
+                        crossings.into_iter().next().map(|crossing| vec![placed, crossing])
+                    })
+                }
+            };
+            for placed in placements.into_iter().flatten() {
+                let (end_row, end_col) = placed.end_position();
+                grid.place_word(&placed.word, end_row, end_col, placed.direction);
+                match list_index(&placed.word) {
+                    Some((Direction::Horizontal, idx)) => used_h_words[idx] = true,
+                    Some((Direction::Vertical, idx)) => used_v_words[idx] = true,
+                    None => {}
+                }
+                placed_words.push(placed);
+            }
+        }
+        (used_h_words, used_v_words)
+    }
+
+    // Every placement of a listed word that crosses partner on a shared letter. The word
+    // runs across the partner, which must be its own list's direction unless directions
+    // are flexible
+    fn crossing_placements(&self, grid: &Grid, placed_words: &[PlacedWord], word: &str, partner: &PlacedWord) -> Vec<PlacedWord> {
+        let direction = partner.direction.perpendicular();
+        let listed = match direction {
+            Direction::Horizontal => &self.horizontal_words,
+            Direction::Vertical => &self.vertical_words,
+        };
+        if !self.config.flexible_directions && !listed.iter().any(|listed| listed == word) {
+            return Vec::new();
+        }
+        let owners = cell_owners(placed_words);
+        partner.cells().zip(partner.word.chars())
+            .flat_map(|((row, col), character)| word.chars().enumerate()
+                .filter(move |&(_, letter)| letter == character)
+                .filter_map(move |(char_idx, _)| match direction {
+                    Direction::Horizontal => col.checked_sub(char_idx).map(|start_col| (row, start_col)),
+                    Direction::Vertical => row.checked_sub(char_idx).map(|start_row| (start_row, col)),
+                }))
+            .map(|(start_row, start_col)| PlacedWord::new(word, start_row, start_col, direction))
+            .filter(|placed| {
+                let (end_row, end_col) = placed.end_position();
+                self.is_allowed_placement(grid, word, end_row, end_col, direction)
+                    && !self.misses_required_crossing(placed_words, word, end_row, end_col, direction)
+                    && !self.crosses_forbidden_word(&owners, placed_words, word, end_row, end_col, direction)
+                    && !self.nests_with_placed_word(&owners, placed_words, word, end_row, end_col, direction)
+            })
+            .collect()
+    }
+
+    // Every placement of a word in one direction that the grid allows
+    fn open_placements(&self, grid: &Grid, word: &str, direction: Direction) -> Vec<PlacedWord> {
+        let len = word.chars().count();
+        let (rows, cols) = match direction {
+            Direction::Horizontal => (grid.height, (grid.width + 1).saturating_sub(len)),
+            Direction::Vertical => ((grid.height + 1).saturating_sub(len), grid.width),
+        };
+        (0..rows).flat_map(|start_row| (0..cols).map(move |start_col| PlacedWord::new(word, start_row, start_col, direction)))
+            .filter(|placed| {
+                let (end_row, end_col) = placed.end_position();
+                self.is_allowed_placement(grid, word, end_row, end_col, direction)
+            })
+            .collect()
+    }
+
+    /// Requested crossings that no layout can satisfy: a word missing from the lists,
+    /// both words running the same way (unless `flexible_directions` lets one of them
+    /// turn, which a pinned word cannot), or no letter in common.
+    ///
+    /// ```
+    /// use wordsearch_generator::{WordLists, WordSearchGenerator};
+    ///
+    /// let mut words = WordLists::new(vec!["APPLE".to_string(), "PLUM".to_string()], vec!["PEAR".to_string(), "FIG".to_string()]);
+    /// words.required_crossings = vec![
+    ///     ("APPLE".to_string(), "PEAR".to_string()),
+    ///     ("APPLE".to_string(), "PLUM".to_string()),
+    ///     ("PLUM".to_string(), "FIG".to_string()),
+    /// ];
+    /// let generator = WordSearchGenerator::new(words, true);
+    /// assert_eq!(generator.impossible_crossings().len(), 2);
+    ///
+    /// // The remaining request is met: APPLE and PEAR share a cell
+    /// let mut words = WordLists::new(vec!["APPLE".to_string(), "PLUM".to_string()], vec!["PEAR".to_string(), "FIG".to_string()]);
+    /// words.required_crossings = vec![("APPLE".to_string(), "PEAR".to_string())];
+    /// let generator = WordSearchGenerator::new(words, true);
+    /// let (_, placed_words) = generator.generate(50).expect("words should fit");
+    /// assert!(generator.required_crossing_cells(&placed_words)[0].2.is_some());
+    /// ```
+    pub fn impossible_crossings(&self) -> Vec<(&String, &String)> {
+        let direction = |word: &String| {
+            if self.horizontal_words.contains(word) {
+                Some(Direction::Horizontal)
+            } else if self.vertical_words.contains(word) {
+                Some(Direction::Vertical)
+            } else {
+                self.pinned.iter().find(|pinned| &pinned.word == word).map(|pinned| pinned.direction)
+            }
+        };
+        self.required_crossings.iter()
+            .filter(|(first, second)| {
+                let (Some(a), Some(b)) = (direction(first), direction(second)) else {
+                    return true;
+                };
+                // Flexible directions let an unpinned word turn across its partner
+                let is_pinned = |word: &String| self.pinned.iter().any(|pinned| &pinned.word == word);
+                let turnable = self.config.flexible_directions && !(is_pinned(first) && is_pinned(second));
+                (a == b && !turnable) || !first.chars().any(|c| second.contains(c))
+            })
+            .map(|(first, second)| (first, second))
+            .collect()
+    }
+
+    // Each requested crossing with the cell where the two words cross in placed_words,
+    // or None if they do not
+    pub fn required_crossing_cells(&self, placed_words: &[PlacedWord]) -> Vec<CrossingCell<'_>> {
+        self.required_crossings.iter()
+            .map(|(first, second)| {
+                let cell = match (self.find_placed(placed_words, first), self.find_placed(placed_words, second)) {
+                    (Some(a), Some(b)) => a.cells().find(|cell| b.cells().any(|other| other == *cell)),
+                    _ => None,
+                };
+                (first, second, cell)
+            })
+            .collect()
+    }
+
+    fn generate_candidates(&self, grid: &Grid, placed_words: &[PlacedWord], word: &str, direction: Direction) -> Vec<PlacementCandidate> {
+        let mut candidates = Vec::new();
+        // Only build the owner map when there are forbidden crossings or nested words to rule out
+        let owners = if self.forbidden_crossings.is_empty() && !self.config.forbid_nested_words {
+            HashMap::new()
+        } else {
+            cell_owners(placed_words)
+        };
+        
+        let len = word.chars().count();
+        if len == 0 {
+            return candidates;
+        }
+        let (rows, cols) = match direction {
+            Direction::Horizontal => (0..grid.height, (len - 1)..grid.width),
+            Direction::Vertical => ((len - 1)..grid.height, 0..grid.width),
+        };
+        let positions: Vec<(usize, usize, bool)> = rows
+            .flat_map(|row| cols.clone().map(move |col| (row, col)))
+            .filter(|&(row, col)| self.is_allowed_placement(grid, word, row, col, direction)
+                && !self.misses_required_crossing(placed_words, word, row, col, direction)
+                && !self.crosses_forbidden_word(&owners, placed_words, word, row, col, direction)
+                && !self.nests_with_placed_word(&owners, placed_words, word, row, col, direction))
+            .map(|(row, col)| (row, col, grid.count_shared_letters(word, row, col, direction) > 0))
+            .collect();
+
+        // Positions crossing a placed letter are always scored. With max_positions_scored
+        // the rest are sampled at an even stride across the grid so that no more than
+        // that many of them are scored
+        let used_area = if self.config.growth_bias.is_some() { grid.bounding_box() } else { None };
+        let non_crossing = positions.iter().filter(|&&(_, _, crossing)| !crossing).count();
+        let stride = self.config.max_positions_scored.map_or(1, |cap| non_crossing.div_ceil(cap.max(1)).max(1));
+        let mut non_crossing_seen = 0;
+        for (row, col, crossing) in positions {
+            if !crossing {
+                non_crossing_seen += 1;
+                if (non_crossing_seen - 1) % stride != 0 {
+                    continue;
+                }
+            }
+            let mut score = self.calculate_placement_score(grid, placed_words, word, row, col, direction);
+            if let (Some(bias), Some(used)) = (self.config.growth_bias, used_area) {
+                score -= growth_outside(bias, used, len, row, col, direction) as f64 * GROWTH_BIAS_PENALTY;
+            }
+            candidates.push(PlacementCandidate {
+                direction,
+                row,
+                col,
+                score,
+            });
+        }
+        
+        // Sort by score (highest first)
+        candidates.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+        
+        // Take top candidates to avoid exponential explosion
+        candidates.truncate(self.config.max_candidates);
+        candidates
+    }
+
+    /// Approximate lower bound on the area of any solution: every letter needs a cell
+    /// except one per crossing, and the bounding box is at least as wide and tall as the
+    /// longest words. The number of crossings is bounded by a maximum matching over the
+    /// pairs of words that share a letter, where each pair crosses at most once and no
+    /// word crosses more often than it has letters.
+    ///
+    /// ```
+    /// use wordsearch_generator::{WordLists, WordSearchGenerator};
+    ///
+    /// let words = WordLists::new(
+    ///     vec!["ONE".to_string(), "TWO".to_string()],
+    ///     vec!["TEN".to_string(), "NINE".to_string()],
+    /// );
+    /// let generator = WordSearchGenerator::new(words, true);
+    ///
+    /// // 13 letters less 3 crossings is below the 3x4 box the longest words need
+    /// assert_eq!(generator.area_lower_bound(), 12);
+    /// let (grid, _) = generator.generate(100).expect("words should fit");
+    /// let (height, width) = grid.get_used_dimensions();
+    /// assert!(height * width >= generator.area_lower_bound());
+    /// ```
+    pub fn area_lower_bound(&self) -> usize {
+        let pinned_words = |direction: Direction| self.pinned.iter()
+            .filter(move |word| word.direction == direction)
+            .map(|word| &word.word);
+        let h_words: Vec<&String> = self.horizontal_words.iter().chain(pinned_words(Direction::Horizontal)).collect();
+        let v_words: Vec<&String> = self.vertical_words.iter().chain(pinned_words(Direction::Vertical)).collect();
+        let h_lens: Vec<usize> = h_words.iter().map(|word| word.chars().count()).collect();
+        let v_lens: Vec<usize> = v_words.iter().map(|word| word.chars().count()).collect();
+
+        let crossable_pairs: Vec<(usize, usize)> = h_words.iter().enumerate()
+            .flat_map(|(h, h_word)| v_words.iter().enumerate().map(move |(v, v_word)| (h, h_word, v, v_word)))
+            .filter(|(_, h_word, _, v_word)| !self.is_forbidden_crossing(h_word, v_word) && h_word.chars().any(|c| v_word.contains(c)))
+            .map(|(h, _, v, _)| (h, v))
+            .collect();
+        let crossings = max_crossings(&h_lens, &v_lens, &crossable_pairs);
+
+        let letters: usize = h_lens.iter().chain(&v_lens).sum();
+        let max_h_len = h_lens.iter().copied().max().unwrap_or(1);
+        let max_v_len = v_lens.iter().copied().max().unwrap_or(1);
+        letters.saturating_sub(crossings).max(max_h_len * max_v_len)
+    }
+
+    /// Rate a finished puzzle from 0 to 100, so puzzles of different sizes and word
+    /// lists can be compared. The score is `100 * (0.5 * compactness + 0.3 * connectivity
+    /// + 0.2 * balance)`, each component running from 0 to 1:
+    ///
+    /// - compactness: [`area_lower_bound`](Self::area_lower_bound) divided by the used area
+    /// - connectivity: share of the placed words crossing at least one other word, among
+    ///   those sharing a letter with a word of the other direction (1 when there are none)
+    /// - balance: shorter side of the used area divided by the longer side
+    ///
+    /// ```
+    /// use wordsearch_generator::{WordLists, WordSearchGenerator};
+    ///
+    /// let words = WordLists::new(vec!["CAT".to_string()], vec!["TEA".to_string()]);
+    /// let generator = WordSearchGenerator::new(words, true);
+    /// let (grid, placed_words) = generator.generate(50).expect("words should fit");
+    /// let quality = generator.quality(&grid, &placed_words);
+    ///
+    /// // The words cross in a 3x3 square, the smallest possible layout
+    /// assert_eq!((quality.compactness, quality.connectivity, quality.balance), (1.0, 1.0, 1.0));
+    /// assert_eq!(quality.score, 100.0);
+    /// ```
+    pub fn quality(&self, grid: &Grid, placed_words: &[PlacedWord]) -> Quality {
+        let (height, width) = grid.get_used_dimensions();
+        let area = (height * width).max(1);
+        let compactness = (self.area_lower_bound() as f64 / area as f64).min(1.0);
+        let balance = height.min(width) as f64 / height.max(width).max(1) as f64;
+
+        // Words that cannot cross anything and hidden reverse copies are left out
+        let disconnected = self.disconnected_words();
+        let crossings = crossings_per_word(placed_words);
+        let crossable: Vec<usize> = placed_words.iter().zip(crossings)
+            .filter(|(word, _)| !word.mirrored && !disconnected.contains(&&word.word))
+            .map(|(_, crossings)| crossings)
+            .collect();
+        let connectivity = if crossable.is_empty() {
+            1.0
+        } else {
+            crossable.iter().filter(|&&crossings| crossings > 0).count() as f64 / crossable.len() as f64
+        };
+
+        let score = 100.0 * (0.5 * compactness + 0.3 * connectivity + 0.2 * balance);
+        Quality { score, compactness, connectivity, balance }
+    }
+
+    // Area at which the attempt loops stop early: a solution at the lower bound (or within
+    // good_enough_area) cannot be meaningfully beaten
+    fn target_area(&self) -> usize {
+        let lower_bound = self.stats.borrow().area_lower_bound;
+        lower_bound.max(self.config.good_enough_area.unwrap_or(0))
+    }
+
+    // Record an early stop after the given attempt once a solution reaches the target area
+    fn reached_target(&self, area: usize, target_area: usize, attempt: usize) -> bool {
+        if area > target_area {
+            return false;
+        }
+        self.attempts_used.set(attempt + 1);
+        if !self.silent {
+            println!("Area {} is within the target of {}, stopping after {} attempts", area, target_area, attempt + 1);
+        }
+        true
+    }
+
+    fn generate_intersection_first(&self, width: usize, height: usize, max_attempts: usize, rng: &mut impl Rng) -> Option<(Grid, Vec<PlacedWord>)> {
+        let intersections = self.find_top_intersections(self.config.max_intersections_considered);
+        let mut best_solution: Option<(Grid, Vec<PlacedWord>)> = None;
+        let mut best_score = f64::NEG_INFINITY;
+
+        let target_area = self.target_area();
+
+        if !self.silent {
+            println!("Intersection-first algorithm: {} intersections", intersections.len());
+        }
+
+        for attempt in 0..max_attempts {
+            if !self.silent && !self.quiet_progress && attempt % 25 == 0 {
+                println!("Intersection-first attempt {}/{}", attempt + 1, max_attempts);
+            }
+
+            let (mut grid, mut placed_words) = self.pinned_layout(width, height)?;
+            let (mut used_h_words, mut used_v_words) = self.seed_required_crossings(&mut grid, &mut placed_words, Some(&mut *rng));
+
+            // Phase 1: Force high-value intersections
+            let mut intersections_copy = intersections.clone();
+            intersections_copy.shuffle(rng);
+            
+            let mut forced_intersections = 0;
+            for intersection in intersections_copy.iter().take(3) { // Try top 3 intersections
+                if used_h_words[intersection.h_word_idx] || used_v_words[intersection.v_word_idx] {
+                    continue;
+                }
+
+                let h_word = &self.horizontal_words[intersection.h_word_idx];
+                let v_word = &self.vertical_words[intersection.v_word_idx];
+
+                // Try to place both words crossing at the best available anchor cell
+                if let Some((row, col)) = self.find_forced_anchor(&grid, intersection) {
+                    let h_start_col = col - intersection.h_char_idx;
+                    let v_start_row = row - intersection.v_char_idx;
+                    let h_end_col = h_start_col + h_word.chars().count() - 1;
+                    let v_end_row = v_start_row + v_word.chars().count() - 1;
+
+                    grid.place_word(h_word, row, h_end_col, Direction::Horizontal);
+                    grid.place_word(v_word, v_end_row, col, Direction::Vertical);
+                    
+                    placed_words.push(PlacedWord::new(h_word.clone(), row, h_start_col, Direction::Horizontal));
+                    
+                    placed_words.push(PlacedWord::new(v_word.clone(), v_start_row, col, Direction::Vertical));
+                    
+                    used_h_words[intersection.h_word_idx] = true;
+                    used_v_words[intersection.v_word_idx] = true;
+                    forced_intersections += 1;
+                }
+            }
+
+            // Phase 2: Place remaining words optimally
+            let mut success = true;
+            
+            // Place remaining horizontal words
+            for (h_idx, h_word) in self.horizontal_words.iter().enumerate() {
+                if used_h_words[h_idx] { continue; }
+                
+                let candidates = self.generate_candidates(&grid, &placed_words, h_word, Direction::Horizontal);
+                let mut placed = false;
+                
+                for candidate in candidates.iter().take(5) {
+                    if grid.place_word(h_word, candidate.row, candidate.col, candidate.direction) {
+                        placed_words.push(PlacedWord::ending_at(h_word.clone(), candidate.row, candidate.col, Direction::Horizontal));
+                        placed = true;
+                        break;
+                    }
+                }
+                
+                if !placed {
+                    success = false;
+                    break;
+                }
+            }
+
+            // Place remaining vertical words
+            if success {
+                for (v_idx, v_word) in self.vertical_words.iter().enumerate() {
+                    if used_v_words[v_idx] { continue; }
+                    
+                    let candidates = self.generate_candidates(&grid, &placed_words, v_word, Direction::Vertical);
+                    let mut placed = false;
+                    
+                    for candidate in candidates.iter().take(5) {
+                        if grid.place_word(v_word, candidate.row, candidate.col, candidate.direction) {
+                            placed_words.push(PlacedWord::ending_at(v_word.clone(), candidate.row, candidate.col, Direction::Vertical));
+                            placed = true;
+                            break;
+                        }
+                    }
+                    
+                    if !placed {
+                        success = false;
+                        break;
+                    }
+                }
+            }
+
+            if success && self.is_acceptable(&placed_words) {
+                self.offer_solution(&grid, &placed_words);
+                let (used_height, used_width) = grid.get_used_dimensions();
+                let area = used_height * used_width;
+                
+                // Enhanced scoring for intersection-first approach
+                let compactness_score = 2000.0 / (area as f64);
+                let square_diff = self.shape_mismatch(used_height, used_width);
+                let squareness_score = 200.0 / (1.0 + square_diff);
+                let intersection_bonus = (forced_intersections + self.count_total_intersections(&placed_words)) as f64 * 25.0;
+                
+                let total_score = self.weighted_score(compactness_score, squareness_score, intersection_bonus);
+                
+                if total_score > best_score {
+                    best_score = total_score;
+                    self.report_improvement("intersection-first", &grid, &placed_words, total_score);
+                    best_solution = Some((grid, placed_words));
+                    
+                    if !self.silent {
+                        println!("Intersection-first solution: area {} ({}x{}), intersections: {}, score: {:.2}", 
+                                area, used_height, used_width, forced_intersections, total_score);
+                    }
+                }
+                if self.reached_target(area, target_area, attempt) {
+                    break;
+                }
+            }
+        }
+
+        best_solution
+    }
+
+    // Range of positions along one axis at which a word's char_idx-th letter can sit
+    // while the whole word stays inside a grid of the given extent
+    fn anchor_range(char_idx: usize, word_len: usize, extent: usize) -> Option<std::ops::RangeInclusive<usize>> {
+        if word_len == 0 || word_len > extent {
+            return None;
+        }
+        Some(char_idx..=(extent - word_len + char_idx))
+    }
+
+    // Find the crossing cell closest to the grid center at which both words of an
+    // intersection fit entirely within the grid and agree with existing letters
+    fn find_forced_anchor(&self, grid: &Grid, intersection: &Intersection) -> Option<(usize, usize)> {
+        let h_word = &self.horizontal_words[intersection.h_word_idx];
+        let v_word = &self.vertical_words[intersection.v_word_idx];
+        let h_len = h_word.chars().count();
+        let v_len = v_word.chars().count();
+
+        let col_range = Self::anchor_range(intersection.h_char_idx, h_len, grid.width)?;
+        let row_range = Self::anchor_range(intersection.v_char_idx, v_len, grid.height)?;
+
+        let center_row = grid.height / 2;
+        let center_col = grid.width / 2;
+        let mut anchors: Vec<(usize, usize)> = row_range
+            .flat_map(|r| col_range.clone().map(move |c| (r, c)))
+            .collect();
+        anchors.sort_by_key(|&(r, c)| r.abs_diff(center_row) + c.abs_diff(center_col));
+
+        anchors.into_iter().find(|&(row, col)| {
+            let h_end_col = col - intersection.h_char_idx + h_len - 1;
+            let v_end_row = row - intersection.v_char_idx + v_len - 1;
+            grid.can_place_word(h_word, row, h_end_col, Direction::Horizontal) &&
+                grid.can_place_word(v_word, v_end_row, col, Direction::Vertical)
+        })
+    }
+
+    // Sum of every word's crossings, so each crossing counts once for each of its two words
+    fn count_total_intersections(&self, placed_words: &[PlacedWord]) -> usize {
+        placed_words.iter()
+            .map(|word| word_crossings(word, placed_words))
+            .sum()
+    }
+
+    fn generate_simulated_annealing(&self, initial_solution: (Grid, Vec<PlacedWord>), iterations: usize, rng: &mut impl Rng) -> (Grid, Vec<PlacedWord>) {
+        let mut current_solution = initial_solution;
+        let mut best_solution = current_solution.clone();
+        // The intersection count is updated incrementally as single words move
+        let mut current_intersections = self.count_total_intersections(&current_solution.1);
+        let mut current_score = self.score_solution(&current_solution.0, current_intersections);
+        let mut best_score = current_score;
+        let mut temperature = self.config.anneal_initial_temperature;
+        let cooling_rate = self.config.anneal_cooling_rate;
+
+        if !self.silent {
+            println!("Starting simulated annealing with {} iterations", iterations);
+        }
+
+        for iteration in 0..iterations {
+            if iteration % 50 == 0 {
+                temperature *= cooling_rate;
+            }
+
+            // Generate a neighbor solution by slightly moving one word
+            let mut new_solution = current_solution.clone();
+            let Some(intersection_delta) = self.try_optimize_single_word(&mut new_solution.0, &mut new_solution.1, rng) else {
+                continue;
+            };
+            if self.is_acceptable(&new_solution.1) {
+                let new_intersections = current_intersections.saturating_add_signed(intersection_delta);
+                debug_assert_eq!(new_intersections, self.count_total_intersections(&new_solution.1),
+                                 "incremental intersection count diverged from a full recount");
+                let new_score = self.score_solution(&new_solution.0, new_intersections);
+                
+                // Accept if better, or with probability if worse
+                let delta = new_score - current_score;
+                if delta > 0.0 || rng.gen::<f64>() < (delta / temperature).exp() {
+                    current_solution = new_solution;
+                    current_intersections = new_intersections;
+                    current_score = new_score;
+                    
+                    if new_score > best_score {
+                        best_score = new_score;
+                        best_solution = current_solution.clone();
+                        self.report_improvement("annealing", &best_solution.0, &best_solution.1, best_score);
+                        
+                        if !self.silent && iteration % 100 == 0 {
+                            let (h, w) = best_solution.0.get_used_dimensions();
+                            println!("SA iteration {}: new best area {} ({}x{}), score: {:.2}", 
+                                   iteration, h * w, h, w, best_score);
+                        }
+                    }
+                }
+            }
+        }
+
+        best_solution
+    }
+
+    // Move one random word to one of its best candidate positions. Returns the change in
+    // the total intersection count, or None if the word could not be moved
+    fn try_optimize_single_word(&self, grid: &mut Grid, placed_words: &mut Vec<PlacedWord>, rng: &mut impl Rng) -> Option<isize> {
+        // Pinned words never move
+        let movable: Vec<usize> = (0..placed_words.len()).filter(|&i| !self.is_pinned(&placed_words[i])).collect();
+        let &word_idx = movable.choose(rng)?;
+        let old_crossings = word_crossings(&placed_words[word_idx], placed_words);
+        
+        // Remove the word temporarily
+        let removed_word = placed_words.remove(word_idx);
+        grid.remove_word(&removed_word, placed_words);
+        
+        // Try to place it in a better position, in either direction when directions are flexible
+        let mut candidates = self.generate_candidates(grid, placed_words, &removed_word.word, removed_word.direction);
+        if self.config.flexible_directions {
+            candidates.extend(self.generate_candidates(grid, placed_words, &removed_word.word, removed_word.direction.perpendicular()));
+            candidates.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+        }
+        
+        for candidate in candidates.iter().take(5) {
+            if grid.place_word(&removed_word.word, candidate.row, candidate.col, candidate.direction) {
+                placed_words.push(PlacedWord::ending_at(removed_word.word.clone(), candidate.row, candidate.col, candidate.direction));
+                // Each crossing gained or lost counts for both of the words involved
+                let new_crossings = word_crossings(&placed_words[placed_words.len() - 1], placed_words);
+                return Some(2 * (new_crossings as isize - old_crossings as isize));
+            }
+        }
+        
+        // If no better position found, put it back in original position
+        let (end_row, end_col) = removed_word.end_position();
+        if grid.place_word(&removed_word.word, end_row, end_col, removed_word.direction) {
+            placed_words.push(removed_word);
+        }
+        
+        None
+    }
+
+    // With GenerationConfig::top above 1, remember an accepted attempt's solution if it
+    // scores among the best distinct ones so far. The list stays sorted, best first, so
+    // the worst kept solution is the one dropped once it is full. Greedy and backtracking
+    // placement stop at their first layout and offer none, which is why validate rejects
+    // top with either of them
+    fn offer_solution(&self, grid: &Grid, placed_words: &[PlacedWord]) {
+        let top = self.config.top;
+        if top <= 1 {
+            return;
+        }
+        let score = self.score_solution(grid, self.count_total_intersections(placed_words));
+        let mut runners_up = self.runners_up.borrow_mut();
+        let full = runners_up.len() >= top;
+        if (full && runners_up.last().is_some_and(|(worst, _, _)| score <= *worst))
+            || runners_up.iter().any(|(_, kept, _)| kept.difference(grid) == 0.0) {
+            return;
+        }
+        let index = runners_up.partition_point(|(kept, _, _)| *kept >= score);
+        runners_up.insert(index, (score, grid.clone(), placed_words.to_vec()));
+        runners_up.truncate(top);
+    }
+
+    // Score of a grid given its total intersection count, which annealing tracks incrementally
+    fn score_solution(&self, grid: &Grid, intersection_count: usize) -> f64 {
+        let (used_height, used_width) = grid.get_used_dimensions();
+        let area = used_height * used_width;
+        let compactness_score = 2000.0 / (area as f64);
+        let square_diff = self.shape_mismatch(used_height, used_width);
+        let squareness_score = 200.0 / (1.0 + square_diff);
+        let intersection_bonus = intersection_count as f64 * 25.0;
+        
+        self.weighted_score(compactness_score, squareness_score, intersection_bonus)
+    }
+
+    // How far a used area is from the preferred shape, in cells: the difference between
+    // its sides, or with a growth bias how far the preferred side falls short of
+    // GROWTH_BIAS_ASPECT times the other
+    fn shape_mismatch(&self, used_height: usize, used_width: usize) -> f64 {
+        let (height, width) = (used_height as f64, used_width as f64);
+        match self.config.growth_bias {
+            Some(GrowthBias::Wide) => (height * GROWTH_BIAS_ASPECT - width).max(0.0),
+            Some(GrowthBias::Tall) => (width * GROWTH_BIAS_ASPECT - height).max(0.0),
+            None => (height - width).abs(),
+        }
+    }
+
+    // Combine the three solution score terms according to the --prefer setting
+    fn weighted_score(&self, compactness_score: f64, squareness_score: f64, intersection_bonus: f64) -> f64 {
+        let (compactness_weight, squareness_weight, intersection_weight) = match self.config.preference {
+            Preference::Compact => (4.0, 1.0, 0.25),
+            Preference::Connected => (0.25, 0.5, 4.0),
+            Preference::Balanced => (1.0, 1.0, 1.0),
+        };
+        compactness_score * compactness_weight + squareness_score * squareness_weight + intersection_bonus * intersection_weight
+    }
+
+    fn generate_optimized(&self, width: usize, height: usize, max_attempts: usize, rng: &mut impl Rng) -> Option<(Grid, Vec<PlacedWord>)> {
+        let intersections = self.find_top_intersections(self.config.max_intersections_considered);
+        let mut best_solution: Option<(Grid, Vec<PlacedWord>)> = None;
+        let mut best_score = f64::NEG_INFINITY;
+
+        let target_area = self.target_area();
+
+        if !self.silent {
+            println!("Found {} potential intersections", intersections.len());
+        }
+
+        for attempt in 0..max_attempts {
+            if !self.silent && !self.quiet_progress && attempt % 50 == 0 {
+                println!("Optimization attempt {}/{}", attempt + 1, max_attempts);
+            }
+
+            let (mut grid, mut placed_words) = self.pinned_layout(width, height)?;
+            let (used_h, used_v) = self.seed_required_crossings(&mut grid, &mut placed_words, Some(&mut *rng));
+            let mut remaining_h: Vec<_> = (0..self.horizontal_words.len()).filter(|&idx| !used_h[idx]).collect();
+            let mut remaining_v: Vec<_> = (0..self.vertical_words.len()).filter(|&idx| !used_v[idx]).collect();
+            
+            // Shuffle to try different orderings, unless a fixed placement order was requested
+            if self.config.placement_order == PlacementOrder::Random {
+                remaining_h.shuffle(rng);
+                remaining_v.shuffle(rng);
+            }
+
+            let mut success = true;
+            let mut placement_queue = self.build_placement_queue(&remaining_h, &remaining_v);
+
+            // Place words using intelligent candidate selection
+            while let Some((word_idx, direction)) = placement_queue.pop_front() {
+                let word = match direction {
+                    Direction::Horizontal => &self.horizontal_words[word_idx],
+                    Direction::Vertical => &self.vertical_words[word_idx],
+                };
+
+                let candidates = self.generate_candidates(&grid, &placed_words, word, direction);
+                
+                let mut placed = false;
+                // Each try takes the best untried candidate with probability `greediness`,
+                // otherwise any candidate at random. Without a greediness the first three
+                // tries take the three best
+                let try_count = candidates.len().clamp(1, 10);
+                let mut next_best = 0;
+                for try_idx in 0..try_count {
+                    let take_best = match self.config.greediness {
+                        Some(greediness) => rng.gen_bool(greediness),
+                        None => try_idx < 3,
+                    };
+                    let candidate_idx = if candidates.is_empty() || take_best {
+                        next_best += 1;
+                        next_best - 1
+                    } else {
+                        rng.gen_range(0..candidates.len())
+                    };
+                    if let Some(candidate) = candidates.get(candidate_idx) {
+                        if grid.place_word(word, candidate.row, candidate.col, candidate.direction) {
+                            placed_words.push(PlacedWord::ending_at(word.clone(), candidate.row, candidate.col, candidate.direction));
+                            placed = true;
+                            break;
+                        }
+                    }
+                }
+
+                if !placed {
+                    success = false;
+                    break;
+                }
+            }
+
+            if success && self.is_acceptable(&placed_words) {
+                self.offer_solution(&grid, &placed_words);
+                let (used_height, used_width) = grid.get_used_dimensions();
+                let area = used_height * used_width;
+                let square_diff = self.shape_mismatch(used_height, used_width);
+                
+                // Enhanced scoring that heavily favors compactness and squareness
+                let compactness_score = 1000.0 / (area as f64);
+                let squareness_score = 100.0 / (1.0 + square_diff);
+                let intersection_bonus = self.count_total_intersections(&placed_words) as f64 * 10.0;
+                
+                let total_score = self.weighted_score(compactness_score, squareness_score, intersection_bonus);
+                
+                if total_score > best_score {
+                    best_score = total_score;
+                    self.report_improvement("optimized", &grid, &placed_words, total_score);
+                    best_solution = Some((grid, placed_words));
+                    
+                    if !self.silent {
+                        println!("Found optimized solution: area {} ({}x{}), score: {:.2}", 
+                                area, used_height, used_width, total_score);
+                    }
+                }
+                if self.reached_target(area, target_area, attempt) {
+                    break;
+                }
+            }
+        }
+
+        best_solution
+    }
+
+    // Build the optimized algorithm's placement queue from the (possibly shuffled) word indices
+    fn build_placement_queue(&self, remaining_h: &[usize], remaining_v: &[usize]) -> VecDeque<(usize, Direction)> {
+        let horizontals = remaining_h.iter().map(|&idx| (idx, Direction::Horizontal));
+        let verticals = remaining_v.iter().map(|&idx| (idx, Direction::Vertical));
+
+        match self.config.queue_interleave {
+            QueueInterleave::Alternate => {
+                // Alternating word types gives each word the best chance to cross the previous one
+                let mut placement_queue = VecDeque::new();
+                let mut h_iter = horizontals;
+                let mut v_iter = verticals;
+                loop {
+                    match (h_iter.next(), v_iter.next()) {
+                        (Some(h), Some(v)) => {
+                            placement_queue.push_back(h);
+                            placement_queue.push_back(v);
+                        }
+                        (Some(h), None) => placement_queue.push_back(h),
+                        (None, Some(v)) => placement_queue.push_back(v),
+                        (None, None) => break,
+                    }
+                }
+                placement_queue
+            }
+            QueueInterleave::HorizontalsFirst => horizontals.chain(verticals).collect(),
+            QueueInterleave::VerticalsFirst => verticals.chain(horizontals).collect(),
+            QueueInterleave::ByLength => {
+                let mut placement_queue: Vec<_> = horizontals.chain(verticals).collect();
+                // Stable sort keeps the shuffled order among words of equal length
+                placement_queue.sort_by_key(|&(idx, direction)| std::cmp::Reverse(match direction {
+                    Direction::Horizontal => self.horizontal_words[idx].chars().count(),
+                    Direction::Vertical => self.vertical_words[idx].chars().count(),
+                }));
+                placement_queue.into()
+            }
+        }
+    }
+
+    // Place every word, longest first, at its single best-scoring candidate. There is
+    // no randomization or retrying so the same input always gives the same result
+    fn generate_greedy(&self, width: usize, height: usize) -> Option<(Grid, Vec<PlacedWord>)> {
+        let (mut grid, mut placed_words) = self.pinned_layout(width, height)?;
+        let (used_h, used_v) = self.seed_required_crossings::<StdRng>(&mut grid, &mut placed_words, None);
+
+        let mut queue: Vec<(&String, Direction)> = unused_words(&self.horizontal_words, &used_h, Direction::Horizontal)
+            .chain(unused_words(&self.vertical_words, &used_v, Direction::Vertical))
+            .collect();
+        queue.sort_by_key(|(word, _)| std::cmp::Reverse(word.chars().count()));
+
+        for (word, direction) in queue {
+            let candidates = self.generate_candidates(&grid, &placed_words, word, direction);
+            let Some(candidate) = candidates.first() else {
+                if !self.silent {
+                    println!("Greedy placement failed: no position for {} ({:?})", word, direction);
+                }
+                return None;
+            };
+
+            grid.place_word(word, candidate.row, candidate.col, direction);
+            placed_words.push(PlacedWord::ending_at(word.clone(), candidate.row, candidate.col, direction));
+        }
+
+        if !self.is_acceptable(&placed_words) {
+            if !self.silent {
+                println!("Greedy placement failed: solution does not meet the requested constraints");
+            }
+            return None;
+        }
+
+        if !self.silent {
+            let (used_height, used_width) = grid.get_used_dimensions();
+            println!("Greedy solution: area {} ({}x{})", used_height * used_width, used_height, used_width);
+        }
+
+        Some((grid, placed_words))
+    }
+
+    // Depth-first search that places the word with the fewest candidate positions next
+    // and, when some word is left with none, undoes the previous placement and tries its
+    // next best candidate. Each word tries at most BACKTRACK_BRANCHING positions, and the
+    // whole search at most max_attempts placements per word, so a hopeless grid fails
+    // in bounded time
+    fn generate_backtracking(&self, width: usize, height: usize, max_attempts: usize) -> Option<(Grid, Vec<PlacedWord>)> {
+        let (mut grid, mut placed_words) = self.pinned_layout(width, height)?;
+        let (used_h, used_v) = self.seed_required_crossings::<StdRng>(&mut grid, &mut placed_words, None);
+
+        let mut remaining: Vec<(&String, Direction)> = unused_words(&self.horizontal_words, &used_h, Direction::Horizontal)
+            .chain(unused_words(&self.vertical_words, &used_v, Direction::Vertical))
+            .collect();
+        remaining.sort_by_key(|(word, _)| std::cmp::Reverse(word.chars().count()));
+
+        let budget = max_attempts.saturating_mul(remaining.len()).max(1);
+        let mut nodes = budget;
+        let found = self.backtrack(&mut grid, &mut placed_words, &mut remaining, &mut nodes);
+        self.attempts_used.set(budget - nodes);
+
+        if !found {
+            if !self.silent {
+                println!("Backtracking failed after {} placements", budget - nodes);
+            }
+            return None;
+        }
+
+        if !self.silent {
+            let (used_height, used_width) = grid.get_used_dimensions();
+            println!("Backtracking solution: area {} ({}x{}) after {} placements", used_height * used_width, used_height, used_width, budget - nodes);
+        }
+
+        Some((grid, placed_words))
+    }
+
+    // Place the most constrained remaining word at each of its best candidates in turn
+    // and recurse on the rest, undoing the placement when the rest cannot be completed.
+    // Fails straight away when any remaining word has no position left. `nodes` is the
+    // number of placements left in the budget
+    fn backtrack(&self, grid: &mut Grid, placed_words: &mut Vec<PlacedWord>, remaining: &mut Vec<(&String, Direction)>,
+                 nodes: &mut usize) -> bool {
+        if remaining.is_empty() {
+            return self.is_acceptable(placed_words);
+        }
+
+        // Ties go to the earlier, i.e. longer, word
+        let mut next: Option<(usize, Vec<PlacementCandidate>)> = None;
+        for (idx, &(word, direction)) in remaining.iter().enumerate() {
+            let candidates = self.generate_candidates(grid, placed_words, word, direction);
+            if candidates.is_empty() {
+                return false;
+            }
+            if next.as_ref().is_none_or(|(_, fewest)| candidates.len() < fewest.len()) {
+                next = Some((idx, candidates));
+            }
+        }
+        let (idx, candidates) = next.expect("remaining is not empty");
+        let (word, direction) = remaining.remove(idx);
+
+        for candidate in candidates.iter().take(BACKTRACK_BRANCHING) {
+            if *nodes == 0 {
+                break;
+            }
+            *nodes -= 1;
+
+            grid.place_word(word, candidate.row, candidate.col, direction);
+            placed_words.push(PlacedWord::ending_at(word.clone(), candidate.row, candidate.col, direction));
+
+            if self.backtrack(grid, placed_words, remaining, nodes) {
+                return true;
+            }
+
+            let undone = placed_words.pop().expect("word was just placed");
+            grid.remove_word(&undone, placed_words);
+        }
+
+        remaining.insert(idx, (word, direction));
+        false
+    }
+
+    fn generate_with_size(&self, width: usize, height: usize, max_attempts: usize, rng: &mut impl Rng) -> Option<(Grid, Vec<PlacedWord>)> {
+        let mut best_solution: Option<(Grid, Vec<PlacedWord>)> = None;
+        let mut best_area = usize::MAX;
+        let target_area = self.target_area();
+
+        // Random anchors are drawn from len-1..extent and 0..extent, which are empty for a
+        // word that cannot fit or a grid with no rows or columns
+        let fits = width > 0 && height > 0
+            && self.horizontal_words.iter().all(|word| word.chars().count() <= width)
+            && self.vertical_words.iter().all(|word| word.chars().count() <= height);
+        if !fits {
+            return None;
+        }
+
+        for attempt in 0..max_attempts {
+            if !self.silent && !self.quiet_progress && attempt % 100 == 0 {
+                println!("Attempt {}/{}", attempt + 1, max_attempts);
+            }
+
+            let (mut grid, mut placed_words) = self.pinned_layout(width, height)?;
+            let (used_h, used_v) = self.seed_required_crossings(&mut grid, &mut placed_words, Some(&mut *rng));
+            let mut remaining_h: Vec<String> = unused_words(&self.horizontal_words, &used_h, Direction::Horizontal)
+                .map(|(word, _)| word.clone())
+                .collect();
+            let mut remaining_v: Vec<String> = unused_words(&self.vertical_words, &used_v, Direction::Vertical)
+                .map(|(word, _)| word.clone())
+                .collect();
+            
+            remaining_h.shuffle(rng);
+            remaining_v.shuffle(rng);
+
+            // Try to place all words
+            let mut success = true;
+            
+            // Place horizontal words (now sorted by length, longest first)
+            for word in &remaining_h {
+                let mut placed = false;
+                let mut attempts = 0;
+                
+                // First try to find good placement considering existing vertical words
+                while !placed && attempts < self.config.placement_tries {
+                    let row = rng.gen_range(0..height);
+                    let col = rng.gen_range(word.chars().count().saturating_sub(1)..width);
+                    
+                    if grid.place_word(word, row, col, Direction::Horizontal) {
+                        placed_words.push(PlacedWord::ending_at(word.clone(), row, col, Direction::Horizontal));
+                        placed = true;
+                    }
+                    attempts += 1;
+                }
+                
+                if !placed {
+                    success = false;
+                    break;
+                }
+            }
+            
+            // Place vertical words (now sorted by length, longest first)
+            if success {
+                for word in &remaining_v {
+                    let mut placed = false;
+                    let mut attempts = 0;
+                    
+                    // Try to place with more attempts for better results
+                    while !placed && attempts < self.config.placement_tries {
+                        let row = rng.gen_range(word.chars().count().saturating_sub(1)..height);
+                        let col = rng.gen_range(0..width);
+                        
+                        if grid.place_word(word, row, col, Direction::Vertical) {
+                            placed_words.push(PlacedWord::ending_at(word.clone(), row, col, Direction::Vertical));
+                            placed = true;
+                        }
+                        attempts += 1;
+                    }
+                    
+                    if !placed {
+                        success = false;
+                        break;
+                    }
+                }
+            }
+
+            if success && self.is_acceptable(&placed_words) {
+                self.offer_solution(&grid, &placed_words);
+                let (used_height, used_width) = grid.get_used_dimensions();
+                let area = used_height * used_width;
+                let square_diff = self.shape_mismatch(used_height, used_width).round() as usize;
+                
+                // Improved scoring: heavily weight area reduction, moderately weight squareness
+                let score = area * 10 + square_diff * 3;
+                
+                if score < best_area {
+                    best_area = score;
+                    best_solution = Some((grid, placed_words));
+                    
+                    if !self.silent {
+                        println!("Found solution with area {} ({}x{}), score: {}", area, used_height, used_width, score);
+                    }
+                }
+                if self.reached_target(area, target_area, attempt) {
+                    break;
+                }
+            }
+        }
+
+        best_solution
+    }
+
+    /// Words in their original form (e.g. with spaces), ordered for the solver's word bank.
+    /// Independent of the order in which the words were placed. With `blank_bank` set,
+    /// that fraction of each word's letters is shown as underscores:
+    ///
+    /// ```
+    /// use wordsearch_generator::{GenerationConfig, WordBankOrder, WordLists, WordSearchGenerator};
+    ///
+    /// let words = WordLists::new(vec!["CATS".to_string()], vec!["TOAST".to_string()]);
+    /// let mut generator = WordSearchGenerator::new(words, true);
+    /// generator.set_config(GenerationConfig { seed: Some(3), blank_bank: Some(0.5), ..GenerationConfig::default() });
+    ///
+    /// let bank = generator.word_bank(WordBankOrder::Alpha);
+    /// assert_eq!(bank[0].matches('_').count(), 2);
+    /// assert_eq!(bank[1].matches('_').count(), 3);
+    /// // The same seed always blanks the same letters
+    /// assert_eq!(bank, generator.word_bank(WordBankOrder::Alpha));
+    /// ```
+    pub fn word_bank(&self, order: WordBankOrder) -> Vec<String> {
+        self.ordered_words(order).iter().map(|word| self.blank_letters(word)).collect()
+    }
+
+    // The word bank before any letters are blanked
+    fn ordered_words(&self, order: WordBankOrder) -> Vec<String> {
+        let mut words = self.input_words.clone();
+        match order {
+            WordBankOrder::Alpha => words.sort_by_key(|word| word.to_lowercase()),
+            WordBankOrder::Length => words.sort_by(|a, b| strip_separators(b).chars().count().cmp(&strip_separators(a).chars().count())
+                .then_with(|| a.to_lowercase().cmp(&b.to_lowercase()))),
+            WordBankOrder::Input => {}
+            WordBankOrder::Shuffled => words.shuffle(&mut *self.rng.borrow_mut()),
+        }
+        words
+    }
+
+    // The word bank split by group: one section per group label, alphabetically, then
+    // the words in no group. Without any groups this is a single unlabeled section
+    pub fn grouped_word_bank(&self, order: WordBankOrder) -> Vec<WordBankGroup> {
+        let words = self.ordered_words(order);
+        let group_of = |word: &String| self.word_groups.get(&strip_separators(word));
+
+        let mut labels: Vec<&String> = self.word_groups.values().collect();
+        labels.sort();
+        labels.dedup();
+        let mut groups: Vec<WordBankGroup> = labels.into_iter()
+            .map(|label| WordBankGroup {
+                label: Some(label.clone()),
+                words: words.iter().filter(|word| group_of(word) == Some(label)).cloned().collect(),
+            })
+            .collect();
+
+        let ungrouped: Vec<String> = words.iter().filter(|word| group_of(word).is_none()).cloned().collect();
+        if !ungrouped.is_empty() || groups.is_empty() {
+            groups.push(WordBankGroup { label: None, words: ungrouped });
+        }
+        for group in &mut groups {
+            group.words = group.words.iter().map(|word| self.blank_letters(word)).collect();
+        }
+        groups
+    }
+
+    // The word with `blank_bank` of its letters (rounded) replaced by underscores. The
+    // blanks depend only on the seed and the word, so every format and every call
+    // shows the same ones
+    fn blank_letters(&self, word: &str) -> String {
+        let Some(ratio) = self.config.blank_bank else {
+            return word.to_string();
+        };
+        let letters: Vec<usize> = word.chars().enumerate()
+            .filter(|&(_, c)| !is_separator(c))
+            .map(|(i, _)| i)
+            .collect();
+        let count = (letters.len() as f64 * ratio).round() as usize;
+        // FNV-1a, so a word's blanks stay the same across runs and platforms
+        let word_hash = word.bytes().fold(0xcbf2_9ce4_8422_2325u64, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x100_0000_01b3));
+        let mut rng = StdRng::seed_from_u64(self.config.seed.unwrap_or(self.bank_seed) ^ word_hash);
+        let blanks: HashSet<usize> = letters.choose_multiple(&mut rng, count).copied().collect();
+        word.chars().enumerate()
+            .map(|(i, c)| if blanks.contains(&i) { '_' } else { c })
+            .collect()
+    }
+
+    /// Words too long to fit the fixed width/height, even after any `auto_grow` growth.
+    /// Generation cannot succeed while this is non-empty.
+    ///
+    /// ```
+    /// use wordsearch_generator::{GenerationConfig, WordLists, WordSearchGenerator};
+    ///
+    /// let words = WordLists::new(vec!["ELEPHANT".to_string()], vec!["CAT".to_string()]);
+    /// let mut generator = WordSearchGenerator::new(words, true);
+    /// generator.set_config(GenerationConfig { fixed_width: Some(5), ..GenerationConfig::default() });
+    ///
+    /// assert_eq!(generator.words_too_long(), vec!["ELEPHANT"]);
+    /// assert!(generator.generate(10).is_none());
+    /// ```
+    pub fn words_too_long(&self) -> Vec<&String> {
+        let growth = if self.config.auto_grow { self.config.max_grow } else { 0 };
+        let h_limit = self.config.fixed_width.map_or(usize::MAX, |width| width + growth);
+        let v_limit = self.config.fixed_height.map_or(usize::MAX, |height| height + growth);
+
+        // A shape or reserved region leaves room only for words as long as the longest
+        // straight run of open cells
+        let fixed_mask = if self.config.fixed_width.is_some() || self.config.fixed_height.is_some() {
+            let (width, height) = self.base_grid_size();
+            self.grid_mask(self.config.fixed_width.unwrap_or(width) + growth, self.config.fixed_height.unwrap_or(height) + growth)
+        } else {
+            None
+        };
+        let (h_limit, v_limit) = match fixed_mask {
+            Some(mask) => (h_limit.min(longest_open_run(&mask, Direction::Horizontal)),
+                           v_limit.min(longest_open_run(&mask, Direction::Vertical))),
+            None => (h_limit, v_limit),
+        };
+        let h_too_long = self.horizontal_words.iter().filter(|word| word.chars().count() > h_limit);
+        let v_too_long = self.vertical_words.iter().filter(|word| word.chars().count() > v_limit);
+        h_too_long.chain(v_too_long).collect()
+    }
+
+    /// Generate a puzzle, returning the compacted grid and the placed words.
+    ///
+    /// Either word list may be empty, in which case the words are simply stacked
+    /// side by side in the one direction:
+    ///
+    /// ```
+    /// use wordsearch_generator::{Direction, WordLists, WordSearchGenerator};
+    ///
+    /// let words = WordLists::new(Vec::new(), vec!["FIVE".to_string(), "SIX".to_string(), "SEVEN".to_string()]);
+    /// let generator = WordSearchGenerator::new(words, true);
+    /// let (grid, placed_words) = generator.generate(50).expect("vertical words should fit");
+    ///
+    /// assert_eq!(placed_words.len(), 3);
+    /// assert!(placed_words.iter().all(|word| word.direction == Direction::Vertical));
+    /// // Parallel words cannot share cells: one column each, as tall as the longest word
+    /// assert_eq!(grid.get_used_dimensions(), (5, 3));
+    /// ```
+    ///
+    /// Words may use any script. Lengths and positions count letters (`char`s), not
+    /// bytes, so Cyrillic words cross on a shared letter just like Latin ones:
+    ///
+    /// ```
+    /// use wordsearch_generator::{count_crossings, WordLists, WordSearchGenerator};
+    ///
+    /// let words = WordLists::new(vec!["КОТ".to_string()], vec!["ТОРТ".to_string()]);
+    /// let generator = WordSearchGenerator::new(words, true);
+    /// let (grid, placed_words) = generator.generate(50).expect("words should fit");
+    ///
+    /// assert_eq!(count_crossings(&placed_words), 1);
+    /// assert!(grid.misplaced_words(&placed_words).is_empty());
+    /// assert_eq!(grid.get_used_dimensions(), (4, 3));
+    /// ```
+    pub fn generate(&self, max_attempts: usize) -> Option<(Grid, Vec<PlacedWord>)> {
+        let mut rng = match self.config.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        let solution = self.generate_with_rng(max_attempts, &mut rng);
+        // The word bank shuffle carries on from the same stream
+        *self.rng.borrow_mut() = rng;
+        solution
+    }
+
+    /// Generate with every random choice drawn from `rng` rather than a generator seeded
+    /// from `seed`, so callers such as tests control the randomness directly. The same
+    /// rng state gives the same puzzle:
+    ///
+    /// ```
+    /// use rand::rngs::StdRng;
+    /// use rand::SeedableRng;
+    /// use wordsearch_generator::{WordLists, WordSearchGenerator};
+    ///
+    /// let words = WordLists::new(
+    ///     vec!["THREE".to_string(), "FOUR".to_string(), "ONE".to_string()],
+    ///     vec!["SEVEN".to_string(), "EIGHT".to_string(), "TEN".to_string()],
+    /// );
+    /// let generator = WordSearchGenerator::new(words, true);
+    /// let first = generator.generate_with_rng(50, &mut StdRng::seed_from_u64(7)).expect("words should fit");
+    /// let second = generator.generate_with_rng(50, &mut StdRng::seed_from_u64(7)).expect("words should fit");
+    ///
+    /// assert_eq!(first.0.cells, second.0.cells);
+    /// ```
+    pub fn generate_with_rng(&self, max_attempts: usize, rng: &mut impl Rng) -> Option<(Grid, Vec<PlacedWord>)> {
+        *self.stats.borrow_mut() = GenerationStats {
+            seed: self.config.seed,
+            area_lower_bound: self.area_lower_bound(),
+            ..GenerationStats::default()
+        };
+
+        if !self.silent {
+            println!("Generating word search puzzle...");
+            println!("Horizontal words: {:?}", self.horizontal_words);
+            println!("Vertical words: {:?}", self.vertical_words);
+            println!();
+        }
+
+        self.runners_up.borrow_mut().clear();
+        self.alternatives.borrow_mut().clear();
+        let solution = if self.config.fixed_width.is_some() || self.config.fixed_height.is_some() {
+            self.generate_fixed_size(max_attempts, rng)
+        } else if let Some(algo_type) = self.config.algorithm {
+            self.generate_single_algorithm(algo_type, max_attempts, rng)
+        } else {
+            self.generate_staged(max_attempts, rng)
+        }?;
+
+        let best = if self.config.top <= 1 {
+            self.post_process(solution, rng)
+        } else {
+            let unprocessed = solution.0.clone();
+            let best = self.post_process(solution, rng);
+            self.process_runners_up(&unprocessed, &best.0, rng);
+            best
+        };
+        // Annealing and compaction reject candidate layouts too, but with a solution
+        // accepted there is no closest rejected one to report
+        {
+            let mut stats = self.stats.borrow_mut();
+            stats.best_rejected_aspect = None;
+            stats.floating_words.clear();
+        }
+        Some(best)
+    }
+
+    /// The runners-up to the solution the last generate call returned, best first, when
+    /// `GenerationConfig::top` asks for more than one. Each has been post-processed like
+    /// the returned solution and differs from it and from every other one in at least
+    /// one cell, so there may be fewer than `top - 1`.
+    ///
+    /// ```
+    /// use wordsearch_generator::{GenerationConfig, WordLists, WordSearchGenerator};
+    ///
+    /// let words = WordLists::new(
+    ///     vec!["THREE".to_string(), "FOUR".to_string(), "ONE".to_string()],
+    ///     vec!["SEVEN".to_string(), "EIGHT".to_string(), "TEN".to_string()],
+    /// );
+    /// let mut generator = WordSearchGenerator::new(words, true);
+    /// generator.set_config(GenerationConfig { top: 3, seed: Some(1), ..GenerationConfig::default() });
+    /// let (best, _) = generator.generate(50).expect("words should fit");
+    ///
+    /// let alternatives = generator.alternatives();
+    /// assert!(!alternatives.is_empty() && alternatives.len() <= 2);
+    /// assert!(alternatives.iter().all(|(grid, _)| grid.difference(&best) > 0.0));
+    /// ```
+    pub fn alternatives(&self) -> Vec<(Grid, Vec<PlacedWord>)> {
+        self.alternatives.borrow().clone()
+    }
+
+    // Post-process the runners-up other than the unprocessed form of the returned solution,
+    // keeping those that still differ from it and from each other afterwards. The stage
+    // timings stay those of the returned solution
+    fn process_runners_up(&self, unprocessed: &Grid, best: &Grid, rng: &mut impl Rng) {
+        let runners_up = std::mem::take(&mut *self.runners_up.borrow_mut());
+        let stats = self.stats();
+        let mut alternatives: Vec<(Grid, Vec<PlacedWord>)> = Vec::new();
+        for (_, grid, placed_words) in runners_up {
+            if alternatives.len() + 1 >= self.config.top {
+                break;
+            }
+            if grid.difference(unprocessed) == 0.0 {
+                continue;
+            }
+            let solution = self.post_process((grid, placed_words), rng);
+            if solution.0.difference(best) > 0.0 && alternatives.iter().all(|(other, _)| other.difference(&solution.0) > 0.0) {
+                alternatives.push(solution);
+            }
+        }
+        *self.stats.borrow_mut() = stats;
+        *self.alternatives.borrow_mut() = alternatives;
+    }
+
+    // The staged schedule: successively larger grids with the algorithms in order of
+    // sophistication, then the standard algorithm on ever larger grids
+    fn generate_staged(&self, max_attempts: usize, rng: &mut impl Rng) -> Option<(Grid, Vec<PlacedWord>)> {
+        let (initial_width, initial_height) = self.base_grid_size();
+        
+        // Try multiple advanced algorithms in order of sophistication, at sizes around the base
+        let algorithms = [
+            (Algorithm::Optimized, 0.6, max_attempts / 5),        // Start very small
+            (Algorithm::IntersectionFirst, 0.7, max_attempts / 5),
+            (Algorithm::Optimized, 0.8, max_attempts / 5),
+            (Algorithm::Optimized, 1.0, max_attempts / 5),
+            (Algorithm::Standard, 1.2, max_attempts / 5),  // Final fallback
+        ];
+        
+        for (algo_type, multiplier, attempts) in algorithms {
+            let width = ((initial_width as f64) * multiplier) as usize;
+            let height = ((initial_height as f64) * multiplier) as usize;
+            
+            if !self.silent {
+                println!("Trying {} algorithm with grid size: {}x{} ({} attempts)", algo_type.name(), width, height, attempts);
+            }
+            
+            if let Some(solution) = self.run_algorithm(algo_type, width, height, attempts, rng) {
+                return Some(solution);
+            }
+        }
+
+        // Last resort: give up on compactness and retry the standard algorithm on larger grids
+        for multiplier in ESCALATION_MULTIPLIERS {
+            let width = ((initial_width as f64) * multiplier) as usize;
+            let height = ((initial_height as f64) * multiplier) as usize;
+
+            if !self.silent {
+                println!("Escalating to {} algorithm with larger grid size: {}x{} ({} attempts)",
+                         Algorithm::Standard.name(), width, height, max_attempts / 5);
+            }
+
+            if let Some(solution) = self.run_algorithm(Algorithm::Standard, width, height, max_attempts / 5, rng) {
+                if !self.silent {
+                    println!("Escalation succeeded at grid size {}x{} ({}x the base size)", width, height, multiplier);
+                }
+                return Some(solution);
+            }
+        }
+
+        None
+    }
+
+    fn run_algorithm(&self, algo_type: Algorithm, width: usize, height: usize, attempts: usize, rng: &mut impl Rng) -> Option<(Grid, Vec<PlacedWord>)> {
+        let start = Instant::now();
+        self.attempts_used.set(attempts);
+        let solution = match algo_type {
+            Algorithm::IntersectionFirst => self.generate_intersection_first(width, height, attempts, rng),
+            Algorithm::Optimized => self.generate_optimized(width, height, attempts, rng),
+            Algorithm::Standard => self.generate_with_size(width, height, attempts, rng),
+            Algorithm::Greedy => {
+                self.attempts_used.set(1);
+                self.generate_greedy(width, height)
+            }
+            Algorithm::Backtrack => self.generate_backtracking(width, height, attempts),
+        };
+
+        if let Some((grid, placed_words)) = &solution {
+            debug_assert!(grid.misplaced_words(placed_words).is_empty(),
+                          "{} placed words that disagree with the grid: {:?}", algo_type.name(), grid.misplaced_words(placed_words));
+        }
+
+        let mut stats = self.stats.borrow_mut();
+        stats.stages.push(StageTiming {
+            algorithm: algo_type,
+            width,
+            height,
+            attempts,
+            attempts_used: self.attempts_used.get(),
+            succeeded: solution.is_some(),
+            duration: start.elapsed(),
+        });
+        if solution.is_some() {
+            stats.winning_algorithm = Some(algo_type);
+        }
+
+        solution
+    }
+
+    /// Statistics gathered during the most recent call to generate
+    pub fn stats(&self) -> GenerationStats {
+        self.stats.borrow().clone()
+    }
+
+    // Run a single algorithm at the estimated grid size with the full attempt budget
+    fn generate_single_algorithm(&self, algo_type: Algorithm, max_attempts: usize, rng: &mut impl Rng) -> Option<(Grid, Vec<PlacedWord>)> {
+        let (width, height) = self.base_grid_size();
+
+        if !self.silent {
+            println!("Running only the {} algorithm with grid size: {}x{} ({} attempts)", algo_type.name(), width, height, max_attempts);
+        }
+
+        self.run_algorithm(algo_type, width, height, max_attempts, rng)
+    }
+
+    fn generate_fixed_size(&self, max_attempts: usize, rng: &mut impl Rng) -> Option<(Grid, Vec<PlacedWord>)> {
+        let (estimated_width, estimated_height) = self.base_grid_size();
+        let mut width = self.config.fixed_width.unwrap_or(estimated_width);
+        let mut height = self.config.fixed_height.unwrap_or(estimated_height);
+        let max_growth = if self.config.auto_grow { self.config.max_grow } else { 0 };
+
+        // At a fixed size there is no point shrinking, so just try each algorithm at that size
+        let algorithms = match self.config.algorithm {
+            Some(algo_type) => vec![(algo_type, max_attempts)],
+            None => vec![
+                (Algorithm::Optimized, max_attempts / 3),
+                (Algorithm::IntersectionFirst, max_attempts / 3),
+                (Algorithm::Standard, max_attempts / 3),
+            ],
+        };
+
+        for growth in 0..=max_growth {
+            if growth > 0 {
+                // Enlarge by one row and one column and try again
+                width += 1;
+                height += 1;
+                if !self.silent {
+                    println!("All algorithms failed, growing grid to {}x{} ({}/{})", width, height, growth, max_growth);
+                }
+            }
+
+            for &(algo_type, attempts) in &algorithms {
+                if !self.silent {
+                    println!("Trying {} algorithm with fixed grid size: {}x{} ({} attempts)", algo_type.name(), width, height, attempts);
+                }
+
+                if let Some(solution) = self.run_algorithm(algo_type, width, height, attempts, rng) {
+                    if !self.silent && growth > 0 {
+                        println!("Succeeded after growing grid to {}x{}", width, height);
+                    }
+                    return Some(solution);
+                }
+            }
+        }
+
+        None
+    }
+
+    // Re-place each word that crosses nothing at the candidate position giving the
+    // smallest used area, keeping the move only if the area shrinks. Repeats until no
+    // floating word can be moved closer
+    fn tighten(&self, grid: &mut Grid, placed_words: &mut Vec<PlacedWord>) {
+        let used_area = |grid: &Grid| {
+            let (height, width) = grid.get_used_dimensions();
+            height * width
+        };
+
+        let mut improved = true;
+        while improved {
+            improved = false;
+            for word_idx in 0..placed_words.len() {
+                if self.is_pinned(&placed_words[word_idx]) || word_crossings(&placed_words[word_idx], placed_words) > 0 {
+                    continue;
+                }
+
+                let mut remaining_words = placed_words.clone();
+                let word = remaining_words.remove(word_idx);
+                let mut remaining_grid = grid.clone();
+                remaining_grid.remove_word(&word, &remaining_words);
+
+                let mut best: Option<(usize, Grid, PlacedWord)> = None;
+                let candidates = self.generate_candidates(&remaining_grid, &remaining_words, &word.word, word.direction);
+                for candidate in candidates {
+                    let mut trial_grid = remaining_grid.clone();
+                    if !trial_grid.place_word(&word.word, candidate.row, candidate.col, candidate.direction) {
+                        continue;
+                    }
+                    let anchored = PlacedWord::ending_at(word.word.clone(), candidate.row, candidate.col, candidate.direction);
+                    let moved = PlacedWord { start_row: anchored.start_row, start_col: anchored.start_col, ..word.clone() };
+                    let area = used_area(&trial_grid);
+                    if best.as_ref().is_none_or(|(best_area, _, _)| area < *best_area) {
+                        best = Some((area, trial_grid, moved));
+                    }
+                }
+
+                if let Some((area, trial_grid, moved)) = best {
+                    let mut trial_words = remaining_words;
+                    trial_words.insert(word_idx, moved);
+                    if area < used_area(grid) && self.is_acceptable(&trial_words) {
+                        *grid = trial_grid;
+                        *placed_words = trial_words;
+                        improved = true;
+                    }
+                }
+            }
+        }
+    }
+
+    // Place each word's letters back to front as an additional hidden word, in the
+    // word's own direction. Words whose reversed copy does not fit are skipped, as
+    // are palindromes whose reverse is the word itself
+    fn place_mirrored_words(&self, grid: &mut Grid, placed_words: &mut Vec<PlacedWord>) {
+        let originals: Vec<PlacedWord> = placed_words.clone();
+        for original in &originals {
+            let reversed_word: String = original.word.chars().rev().collect();
+            if reversed_word == original.word {
+                continue;
+            }
+
+            let candidates = self.generate_candidates(grid, placed_words, &reversed_word, original.direction);
+            for candidate in candidates.iter().take(5) {
+                let mirrored = PlacedWord {
+                    reversed: true,
+                    mirrored: true,
+                    ..PlacedWord::ending_at(original.word.clone(), candidate.row, candidate.col, candidate.direction)
+                };
+
+                // Forbidden crossings and crossing limits apply to the hidden copy too
+                let mut trial = placed_words.clone();
+                trial.push(mirrored.clone());
+                if self.is_acceptable(&trial) && grid.place_word(&reversed_word, candidate.row, candidate.col, candidate.direction) {
+                    placed_words.push(mirrored);
+                    break;
+                }
+            }
+        }
+    }
+
+    fn post_process(&self, solution: (Grid, Vec<PlacedWord>), rng: &mut impl Rng) -> (Grid, Vec<PlacedWord>) {
+        let (mut grid, mut placed_words) = solution;
+        let original_area = grid.get_used_dimensions().0 * grid.get_used_dimensions().1;
+        
+        // Phase 1: Apply simulated annealing for local optimization
+        if self.config.anneal_iterations > 0 {
+            if !self.silent {
+                println!("Applying simulated annealing optimization...");
+            }
+            let annealing_start = Instant::now();
+            let (optimized_grid, optimized_words) = self.generate_simulated_annealing((grid, placed_words), self.config.anneal_iterations, rng);
+            grid = optimized_grid;
+            placed_words = optimized_words;
+            self.stats.borrow_mut().annealing_time = annealing_start.elapsed();
+        }
+        
+        // Phase 2: Pull words without crossings in towards the rest of the layout
+        self.tighten(&mut grid, &mut placed_words);
+
+        // Phase 3: Hide a back-to-front copy of each word wherever it fits
+        if self.config.include_reversed {
+            self.place_mirrored_words(&mut grid, &mut placed_words);
+        }
+
+        // Phase 4: Compact the grid. A shaped grid keeps its full outline, and pinned
+        // words and the reserved region keep their coordinates
+        let compaction_start = Instant::now();
+        if self.config.skip_compaction {
+            grid.uncropped = true;
+        } else if self.config.shape.is_none() && self.pinned.is_empty() && self.config.reserved.is_none() {
+            let (row_offset, col_offset) = grid.compact();
+
+            // Update word positions after compaction
+            for word in placed_words.iter_mut() {
+                word.start_row = word.start_row.saturating_sub(row_offset);
+                word.start_col = word.start_col.saturating_sub(col_offset);
+            }
+
+            // Phase 5: Try aggressive compaction (removing gaps would make separated words
+            // touch). Each removal narrows one side or brings words together, so stop
+            // before breaking max_aspect or valid_crossword
+            let mut before = (self.config.max_aspect.is_some() || self.config.valid_crossword)
+                .then(|| (grid.clone(), placed_words.clone()));
+            while !self.config.strict_separation && grid.try_remove_empty_rows_cols(&mut placed_words) {
+                if let Some((previous_grid, previous_words)) = before.take() {
+                    if !self.is_acceptable(&placed_words) {
+                        grid = previous_grid;
+                        placed_words = previous_words;
+                        break;
+                    }
+                    before = Some((grid.clone(), placed_words.clone()));
+                }
+            }
+        }
+
+        self.stats.borrow_mut().compaction_time = compaction_start.elapsed();
+
+        // Phase 6: Present the grid in the requested orientation. Words are generated in
+        // natural reading order; for reversed reading they were laid out half-turned, and
+        // the half turn back makes every one of them read backwards
+        let orientation = match self.config.reading_order {
+            ReadingOrder::Natural => self.config.orientation,
+            ReadingOrder::Reversed => self.config.orientation.rotated_half_turn(),
+        };
+        grid.reorient(orientation, &mut placed_words);
+        if self.config.transpose {
+            grid.transpose(&mut placed_words);
+        }
+
+        // Phase 7: Pad with an empty margin for printing
+        grid.add_margin(self.config.margin, &mut placed_words);
+
+        for word in placed_words.iter_mut() {
+            word.display = self.display_forms.get(&word.word).cloned();
+            word.group = self.word_groups.get(&word.word).cloned();
+        }
+        
+        debug_assert!(grid.misplaced_words(&placed_words).is_empty(),
+                      "placed words disagree with the grid: {:?}", grid.misplaced_words(&placed_words));
+
+        self.stats.borrow_mut().quality = Some(self.quality(&grid, &placed_words));
+
+        let final_area = grid.get_used_dimensions().0 * grid.get_used_dimensions().1;
+        if !self.silent {
+            println!("Total optimization: {} -> {} area ({:.1}% reduction)", 
+                     original_area, final_area, 
+                     100.0 * (1.0 - final_area as f64 / original_area as f64));
+        }
+        
+        (grid, placed_words)
+    }
+}
@@ -1,1123 +1,1263 @@
-use clap::Parser;
-use rand::seq::SliceRandom;
-use rand::Rng;
+use clap::parser::ValueSource;
+use clap::{ArgMatches, CommandFactory, FromArgMatches, Parser, ValueEnum};
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
-use std::collections::VecDeque;
+use std::cmp::Reverse;
 use std::fs;
-use std::path::PathBuf;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use wordsearch_generator::{
+    color_allowed, count_crossings, strip_separators, Algorithm, AnswerKeyStyle, GenerationConfig, GenerationStats, Grid, GrowthBias, Orientation, Origin, OutputCase, OutputFormat, PageSize, PlacedWord, PlacementOrder,
+    Preference, PuzzleExport, PuzzleSnapshot, QueueInterleave, ReadingOrder, Region, RenderStyle, Sections, Shape, WordBankOrder, WordListAnalysis, WordLists, WordSearchGenerator,
+};
+
+// Process exit codes, so scripts can tell the outcomes apart without parsing output
+const EXIT_FAILURE: i32 = 1;
+const EXIT_PARTIAL: i32 = 2;
+const EXIT_INVALID_INPUT: i32 = 3;
+
+// Defaults of --max-intersections-considered and --max-candidates under --low-memory
+const LOW_MEMORY_INTERSECTIONS: usize = 500;
+const LOW_MEMORY_CANDIDATES: usize = 10;
+
+// Seeds tried for each --variants layout before giving up on finding a distinct one
+const VARIANT_SEED_TRIES: usize = 20;
+
+// Word lists timed by --benchmark, from small and easy to large, with two lists at the
+// extremes of how readily words cross
+const BENCHMARK_INPUTS: [(&str, &str); 5] = [
+    ("small", include_str!("../small_example.yaml")),
+    ("medium", include_str!("../benchmark/medium.yaml")),
+    ("large", include_str!("../example_words.yaml")),
+    ("sparse", include_str!("../benchmark/sparse.yaml")),
+    ("dense", include_str!("../benchmark/dense.yaml")),
+];
+
+// Seeds 0..BENCHMARK_RUNS are generated for each --benchmark input
+const BENCHMARK_RUNS: u64 = 5;
+
+// Result of generating a single puzzle
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Outcome {
+    Complete,
+    // A puzzle was produced but some words were left out
+    Partial,
+    Failed,
+}
+
+// Timing and quality of the --benchmark runs of one input. Area, intersections and
+// compactness are averaged over the successful runs only
+#[derive(Debug, Serialize)]
+struct BenchmarkResult {
+    input: &'static str,
+    words: usize,
+    runs: u64,
+    successes: u64,
+    mean_time: Duration,
+    mean_area: Option<f64>,
+    mean_intersections: Option<f64>,
+    // Area over the lower bound of GenerationStats::area_lower_bound
+    mean_compactness: Option<f64>,
+}
+
+// Layouts of the current word list already output by --variants, and the next seed to try
+struct Variants {
+    earlier: Vec<PuzzleSnapshot>,
+    next_seed: u64,
+    // Set once no seed gave a layout distinct enough from the earlier ones
+    exhausted: bool,
+}
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
     /// Input YAML file containing word lists
-    #[arg(short, long)]
-    input: PathBuf,
+    #[arg(short, long, required_unless_present_any = ["list_algorithms", "benchmark", "h_word", "v_word"])]
+    input: Option<PathBuf>,
+
+    /// Add a horizontal word (repeatable), with or without --input
+    #[arg(long = "h-word", value_name = "WORD")]
+    h_word: Vec<String>,
+
+    /// Add a vertical word (repeatable), with or without --input
+    #[arg(long = "v-word", value_name = "WORD")]
+    v_word: Vec<String>,
+
+    /// Run only this algorithm (at the estimated grid size) instead of the staged schedule
+    #[arg(long, value_enum)]
+    algorithm: Option<Algorithm>,
+
+    /// Report letter frequencies and intersection potential of the word lists without generating
+    #[arg(long)]
+    analyze: bool,
+
+    /// List the available algorithms and exit
+    #[arg(long)]
+    list_algorithms: bool,
+
+    /// Time generation of a bundled set of word lists with the current settings and print
+    /// a table of the results (JSON with --format json) instead of a puzzle
+    #[arg(long)]
+    benchmark: bool,
 
     /// Disable progress indication
     #[arg(short, long)]
     silent: bool,
 
+    /// Hide the periodic "attempt X/Y" progress lines but keep other messages
+    #[arg(long)]
+    quiet_progress: bool,
+
+    /// Load generation settings from this YAML file; options given on the command line override it
+    #[arg(long, value_name = "PATH")]
+    config: Option<PathBuf>,
+
+    /// Write the generation settings in effect to this YAML file, for reuse with --config
+    #[arg(long, value_name = "PATH")]
+    save_config: Option<PathBuf>,
+
     /// Maximum attempts to find optimal solution
     #[arg(long, default_value_t = 1000)]
     max_attempts: usize,
-}
 
-#[derive(Debug, Deserialize, Serialize)]
-struct WordLists {
-    horizontal: Vec<String>,
-    vertical: Vec<String>,
-}
+    /// Seed for reproducible output: the same seed, options and words give the same puzzle
+    #[arg(long, conflicts_with = "seed_sweep")]
+    seed: Option<u64>,
+
+    /// Generate with seeds 0..N in parallel and output the best, reporting its seed
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u64).range(1..))]
+    seed_sweep: Option<u64>,
+
+    /// Generate K distinct layouts of each word list, e.g. worksheet variants (numbered like --output)
+    #[arg(long, value_name = "K", value_parser = clap::value_parser!(u64).range(1..), conflicts_with = "seed_sweep")]
+    variants: Option<u64>,
+
+    /// Fraction of cells in which each --variants layout must differ from every earlier one
+    #[arg(long, value_name = "FRACTION", default_value_t = 0.3)]
+    variant_difference: f64,
+
+    /// Output the K best distinct solutions found in one run, best first (numbered like --output)
+    #[arg(long, value_name = "K", value_parser = clap::value_parser!(u64).range(1..), conflicts_with = "variants")]
+    top: Option<u64>,
+
+    /// Colorize letters by word direction (only when writing to a terminal, honors NO_COLOR)
+    #[arg(long)]
+    color: bool,
+
+    /// Fixed grid width (the height is estimated unless --height is also given)
+    #[arg(long, value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+    width: Option<usize>,
+
+    /// Fixed grid height (the width is estimated unless --width is also given)
+    #[arg(long, value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+    height: Option<usize>,
+
+    /// With a fixed size, grow the grid by one row and column after each failed round
+    #[arg(long)]
+    auto_grow: bool,
+
+    /// Maximum number of rows/columns --auto-grow may add
+    #[arg(long, default_value_t = 10)]
+    max_grow: usize,
+
+    /// Random positions the standard algorithm tries per word before abandoning an attempt
+    #[arg(long, default_value_t = 150)]
+    placement_tries: usize,
+
+    /// Most promising intersections the placement algorithms work from (large lists are trimmed to this many)
+    /// [default: 10000, or 500 with --low-memory]
+    #[arg(long)]
+    max_intersections_considered: Option<usize>,
 
-#[derive(Debug, Clone)]
-struct Grid {
-    cells: Vec<Vec<Option<char>>>,
-    width: usize,
-    height: usize,
-}
+    /// Best-scoring positions kept per word when choosing where to place it
+    /// [default: 50, or 10 with --low-memory]
+    #[arg(long)]
+    max_candidates: Option<usize>,
 
-#[derive(Debug, Clone)]
-struct PlacedWord {
-    word: String,
-    start_row: usize,
-    start_col: usize,
-    direction: Direction,
-}
+    /// Score at most this many positions per word that cross no placed letter, sampled
+    /// evenly across the grid (positions crossing a letter are always scored)
+    #[arg(long, value_name = "N")]
+    max_positions_scored: Option<usize>,
+
+    /// Lower the defaults of --max-intersections-considered and --max-candidates for a
+    /// smaller, predictable memory ceiling at some cost in solution quality
+    #[arg(long)]
+    low_memory: bool,
+
+    /// Chance (0 to 1) that the optimized algorithm places each word at its best position rather than a random one
+    /// [default: the three best positions, then random ones]
+    #[arg(long)]
+    greediness: Option<f64>,
 
-#[derive(Debug, Clone, Copy, PartialEq)]
-enum Direction {
-    Horizontal, // right-to-left
-    Vertical,   // bottom-to-top
-}
+    /// Stop an algorithm's attempts as soon as a solution's area is at most this
+    #[arg(long)]
+    good_enough_area: Option<usize>,
 
-#[derive(Debug, Clone)]
-struct Intersection {
-    h_word_idx: usize,
-    v_word_idx: usize,
-    h_char_idx: usize,
-    v_char_idx: usize,
-    character: char,
-}
+    /// Multiplier applied to the estimated grid size, at most 10 (larger is easier but looser)
+    #[arg(long, default_value_t = 1.0)]
+    size_factor: f64,
 
-#[derive(Debug, Clone)]
-struct PlacementCandidate {
-    word_idx: usize,
-    direction: Direction,
-    row: usize,
-    col: usize,
-    score: f64,
-    intersections: Vec<usize>, // indices of intersections this placement would create
-}
+    /// Trade-off used to pick the winning solution
+    #[arg(long, value_enum, default_value_t = Preference::Balanced)]
+    prefer: Preference,
+
+    /// Start with a wider than tall grid and discourage placements that add rows
+    #[arg(long, conflicts_with = "prefer_height")]
+    prefer_width: bool,
+
+    /// Start with a taller than wide grid and discourage placements that add columns
+    #[arg(long)]
+    prefer_height: bool,
+
+    /// Reject any solution whose used region is more than RATIO times as long one way as the other
+    #[arg(long, value_name = "RATIO")]
+    max_aspect: Option<f64>,
+
+    /// Skip the simulated annealing post-processing (compaction still runs)
+    #[arg(long)]
+    no_anneal: bool,
+
+    /// Keep the grid at its generated size, empty borders included, with word positions in that frame
+    #[arg(long)]
+    no_compact: bool,
+
+    /// Let annealing turn a word horizontal or vertical, whichever list it came from
+    #[arg(long)]
+    flexible_directions: bool,
+
+    /// Number of simulated annealing iterations applied to each solution
+    #[arg(long, visible_alias = "sa-iterations", default_value_t = 100)]
+    anneal_iterations: usize,
+
+    /// Starting simulated annealing temperature (higher accepts more worse moves early on)
+    #[arg(long, default_value_t = 1000.0)]
+    sa_initial_temp: f64,
+
+    /// Factor applied to the annealing temperature every 50 iterations (0 < rate <= 1)
+    #[arg(long, default_value_t = 0.95)]
+    sa_cooling_rate: f64,
+
+    /// Order in which words are queued for placement
+    #[arg(long, value_enum, default_value_t = PlacementOrder::Random)]
+    placement_order: PlacementOrder,
+
+    /// How the optimized algorithm interleaves horizontal and vertical words
+    #[arg(long, value_enum, default_value_t = QueueInterleave::Alternate)]
+    interleave: QueueInterleave,
+
+    /// Keep every word isolated: no crossings and no side or corner contact between words
+    #[arg(long)]
+    strict_separation: bool,
+
+    /// Reject solutions where adjacent letters form a run, across or down, that is not a placed word
+    #[arg(long)]
+    valid_crossword: bool,
+
+    /// Never place a word wholly on the letters of a longer word, e.g. CAT inside CATALOG
+    #[arg(long)]
+    no_nested_words: bool,
+
+    /// Maximum number of other words any single word may cross
+    #[arg(long)]
+    max_intersections_per_word: Option<usize>,
+
+    /// Warn about pairs of words within this many letter edits of each other (default: 1), which often indicates a typo
+    #[arg(long, value_name = "DISTANCE", num_args = 0..=1, default_missing_value = "1")]
+    warn_similar: Option<usize>,
+
+    /// Weight each crossing by the rarity of its letter instead of a flat bonus
+    #[arg(long)]
+    rare_letter_bonus: bool,
+
+    /// Favor crossings on these letters, e.g. "aeiou" (a soft preference)
+    #[arg(long, value_name = "SET")]
+    prefer_intersection_letters: Option<String>,
+
+    /// Also hide a back-to-front copy of each word, where it fits
+    #[arg(long)]
+    include_reversed: bool,
+
+    /// Spread words out toward the edges instead of clustering them around the center
+    #[arg(long)]
+    spread: bool,
+
+    /// Only accept solutions in which every word crosses at least one other word
+    #[arg(long)]
+    all_words_connected: bool,
+
+    /// Fail if any word shares no letters with the words of the other direction
+    #[arg(long)]
+    require_connected: bool,
+
+    /// Print each new best grid as it is found during generation
+    #[arg(long)]
+    live_preview: bool,
+
+    /// After generation, print the grid once per placed word with the words added cumulatively
+    #[arg(long)]
+    trace_placements: bool,
+
+    /// After generation, write one SVG image per placed word to this directory, adding the words cumulatively
+    #[arg(long, value_name = "DIR")]
+    frames_dir: Option<PathBuf>,
+
+    /// Fit the words inside this outline instead of a rectangle
+    #[arg(long, value_enum)]
+    shape: Option<Shape>,
+
+    /// Keep the rectangle between rows R1-R2 and columns C1-C2 (from 0) free of words, e.g. for a logo
+    #[arg(long, value_name = "R1,C1,R2,C2")]
+    reserve: Option<Region>,
+
+    /// Direction the letters of each word read in
+    #[arg(long, value_enum, default_value_t = ReadingOrder::Natural)]
+    reading_order: ReadingOrder,
+
+    /// Flip or rotate the finished grid
+    #[arg(long, value_enum, default_value_t = Orientation::Normal)]
+    orient: Orientation,
+
+    /// Swap the rows and columns of the finished grid, so horizontal words read down
+    #[arg(long)]
+    transpose: bool,
 
-#[derive(Debug, Clone)]
-struct ConstraintState {
-    placed_words: Vec<PlacedWord>,
-    available_intersections: Vec<Intersection>,
-    remaining_h_words: Vec<usize>,
-    remaining_v_words: Vec<usize>,
+    /// Add this many empty rows and columns around the finished grid
+    #[arg(long, default_value_t = 0)]
+    margin: usize,
+
+    /// Show row and column numbers around the grid
+    #[arg(long)]
+    coords: bool,
+
+    /// Corner of the grid that coordinates in the output and answer key count from
+    #[arg(long, value_enum, default_value_t = Origin::TopLeft)]
+    origin: Origin,
+
+    /// Write the puzzle to this file instead of standard output (numbered per puzzle for multi-document input)
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+
+    /// Output just the grid, without title, word bank, instructions or answer key (text, HTML, JSON and PDF)
+    #[arg(long, conflicts_with = "solution_only")]
+    grid_only: bool,
+
+    /// Output just the answer key (text, HTML, JSON and PDF)
+    #[arg(long)]
+    solution_only: bool,
+
+    /// Also write just the answer key, in the same format, to this file (numbered like --output)
+    #[arg(long, value_name = "PATH")]
+    solution_output: Option<PathBuf>,
+
+    /// Output format for the grid
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+
+    /// Order of the word bank listed beneath the grid (text and HTML formats)
+    #[arg(long, value_enum, default_value_t = WordBankOrder::Alpha)]
+    word_bank: WordBankOrder,
+
+    /// Show this fraction (0 to 1) of each word's letters as underscores in the word bank, for a fill-in activity
+    #[arg(long, value_name = "RATIO")]
+    blank_bank: Option<f64>,
+
+    /// Paper size for PDF output
+    #[arg(long, value_enum, default_value_t = PageSize::A4)]
+    page_size: PageSize,
+
+    /// Check that every placed word reads correctly from its recorded position, failing if not
+    #[arg(long)]
+    verify: bool,
+
+    /// Compare the generated puzzle with one previously saved with --format json
+    #[arg(long, value_name = "OTHER_JSON")]
+    diff: Option<PathBuf>,
+
+    /// Include the answer key (text, HTML and PDF formats)
+    #[arg(long)]
+    answer_key: bool,
+
+    /// How the answer key lists each word
+    #[arg(long, value_enum, default_value_t = AnswerKeyStyle::Table)]
+    answer_key_style: AnswerKeyStyle,
+
+    /// Character shown for empty cells (default: '.' for text, blank for CSV)
+    #[arg(long)]
+    empty_char: Option<char>,
+
+    /// Letter case used when rendering the grid (defaults to the case of the input words)
+    #[arg(long, value_enum)]
+    output_case: Option<OutputCase>,
 }
 
-impl Grid {
-    fn new(width: usize, height: usize) -> Self {
-        Grid {
-            cells: vec![vec![None; width]; height],
-            width,
-            height,
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Usage errors share the invalid-input code rather than clap's own 2, which means a partial result here
+    let matches = match Args::command().try_get_matches() {
+        Ok(matches) => matches,
+        Err(error) => {
+            error.print()?;
+            std::process::exit(if error.use_stderr() { EXIT_INVALID_INPUT } else { 0 });
         }
+    };
+    let args = Args::from_arg_matches(&matches)?;
+
+    if args.list_algorithms {
+        for algo_type in Algorithm::value_variants() {
+            let help = algo_type.to_possible_value()
+                .and_then(|value| value.get_help().map(|help| help.to_string()))
+                .unwrap_or_default();
+            println!("{:<20} {}", algo_type.name(), help);
+        }
+        return Ok(());
     }
 
-    fn can_place_word(&self, word: &str, row: usize, col: usize, direction: Direction) -> bool {
-        let chars: Vec<char> = word.chars().collect();
-        
-        match direction {
-            Direction::Horizontal => {
-                // Check if word fits (right-to-left)
-                if col + 1 < chars.len() {
-                    return false;
-                }
-                let start_col = col + 1 - chars.len();
-                
-                // Check each position
-                for (i, &ch) in chars.iter().enumerate() {
-                    let c = start_col + i;
-                    if let Some(existing) = self.cells[row][c] {
-                        if existing != ch {
-                            return false;
-                        }
-                    }
-                }
-                true
-            }
-            Direction::Vertical => {
-                // Check if word fits (bottom-to-top)
-                if row + 1 < chars.len() {
-                    return false;
-                }
-                let start_row = row + 1 - chars.len();
-                
-                // Check each position
-                for (i, &ch) in chars.iter().enumerate() {
-                    let r = start_row + i;
-                    if let Some(existing) = self.cells[r][col] {
-                        if existing != ch {
-                            return false;
-                        }
-                    }
-                }
-                true
-            }
+    // Read and parse the input file, which may hold several puzzles as separate YAML documents.
+    // Words given with --h-word/--v-word are added to every puzzle, or form the only
+    // puzzle when there is no input file
+    let mut documents = match &args.input {
+        Some(input_path) => {
+            let input_content = fs::read_to_string(input_path).unwrap_or_else(|e| {
+                eprintln!("Error: cannot read {}: {}", input_path.display(), e);
+                std::process::exit(EXIT_INVALID_INPUT);
+            });
+            serde_yaml::Deserializer::from_str(&input_content)
+                .map(WordLists::deserialize)
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap_or_else(|e| {
+                    eprintln!("Error: invalid input file {}: {}", input_path.display(), e);
+                    std::process::exit(EXIT_INVALID_INPUT);
+                })
         }
+        None => vec![WordLists::new(Vec::new(), Vec::new())],
+    };
+    for word_lists in &mut documents {
+        merge_words(&mut word_lists.horizontal, &args.h_word);
+        merge_words(&mut word_lists.vertical, &args.v_word);
     }
 
-    fn place_word(&mut self, word: &str, row: usize, col: usize, direction: Direction) -> bool {
-        if !self.can_place_word(word, row, col, direction) {
-            return false;
+    let config = generation_config(&args, &matches)
+        .and_then(|config| config.validate().map(|_| config))
+        .unwrap_or_else(|e| {
+            eprintln!("Error: {}", e);
+            std::process::exit(EXIT_INVALID_INPUT);
+        });
+    if let Some(path) = &args.save_config {
+        fs::write(path, serde_yaml::to_string(&config)?)?;
+        if !args.silent {
+            println!("Settings written to {}", path.display());
         }
+    }
 
-        let chars: Vec<char> = word.chars().collect();
-        
-        match direction {
-            Direction::Horizontal => {
-                let start_col = col + 1 - chars.len();
-                for (i, &ch) in chars.iter().enumerate() {
-                    self.cells[row][start_col + i] = Some(ch);
-                }
-            }
-            Direction::Vertical => {
-                let start_row = row + 1 - chars.len();
-                for (i, &ch) in chars.iter().enumerate() {
-                    self.cells[start_row + i][col] = Some(ch);
-                }
-            }
-        }
-        true
-    }
-
-    fn calculate_used_area(&self) -> (usize, usize, usize, usize) {
-        let mut min_row = self.height;
-        let mut max_row = 0;
-        let mut min_col = self.width;
-        let mut max_col = 0;
-        let mut has_content = false;
-
-        for (r, row) in self.cells.iter().enumerate() {
-            for (c, cell) in row.iter().enumerate() {
-                if cell.is_some() {
-                    has_content = true;
-                    min_row = min_row.min(r);
-                    max_row = max_row.max(r);
-                    min_col = min_col.min(c);
-                    max_col = max_col.max(c);
-                }
-            }
+    if args.benchmark {
+        let results = run_benchmark(&args, &config);
+        match args.format {
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&results)?),
+            _ => print_benchmark(&results),
         }
+        return Ok(());
+    }
 
-        if has_content {
-            (min_row, max_row, min_col, max_col)
-        } else {
-            (0, 0, 0, 0)
-        }
+    if (args.grid_only || args.solution_only || args.solution_output.is_some())
+        && matches!(args.format, OutputFormat::Csv | OutputFormat::Dot) {
+        eprintln!("Error: --grid-only, --solution-only and --solution-output apply to text, HTML, JSON and PDF output");
+        std::process::exit(EXIT_INVALID_INPUT);
     }
 
-    fn get_used_dimensions(&self) -> (usize, usize) {
-        let (min_row, max_row, min_col, max_col) = self.calculate_used_area();
-        (max_row - min_row + 1, max_col - min_col + 1)
+    if !(0.0..=1.0).contains(&args.variant_difference) {
+        eprintln!("Error: --variant-difference must be between 0 and 1");
+        std::process::exit(EXIT_INVALID_INPUT);
     }
 
-    fn compact(&mut self) -> (usize, usize) {
-        let (min_row, max_row, min_col, max_col) = self.calculate_used_area();
-        
-        // Create a new compacted grid
-        let new_height = max_row - min_row + 1;
-        let new_width = max_col - min_col + 1;
-        let mut new_cells = vec![vec![None; new_width]; new_height];
-        
-        for r in 0..new_height {
-            for c in 0..new_width {
-                new_cells[r][c] = self.cells[min_row + r][min_col + c];
-            }
-        }
-        
-        self.cells = new_cells;
-        self.width = new_width;
-        self.height = new_height;
-        
-        (min_row, min_col) // Return offset for updating word positions
-    }
-
-    fn try_remove_empty_rows_cols(&mut self) -> bool {
-        let mut changed = false;
-        
-        // Try to remove empty rows
-        let mut row = 0;
-        while row < self.height {
-            let row_empty = (0..self.width).all(|c| self.cells[row][c].is_none());
-            if row_empty {
-                self.cells.remove(row);
-                self.height -= 1;
-                changed = true;
-            } else {
-                row += 1;
+    if documents.is_empty() {
+        eprintln!("Error: No words provided in input file");
+        std::process::exit(EXIT_INVALID_INPUT);
+    }
+
+    // Each word list gives one puzzle, or one per variant with --variants
+    let variant_count = args.variants.unwrap_or(1) as usize;
+    let count = documents.len() * variant_count;
+    let mut worst_outcome = Outcome::Complete;
+    for (document_index, word_lists) in documents.into_iter().enumerate() {
+        let mut variants = args.variants.map(|_| Variants {
+            earlier: Vec::new(),
+            next_seed: config.seed.unwrap_or_else(rand::random),
+            exhausted: false,
+        });
+        for variant in 0..variant_count {
+            let index = document_index * variant_count + variant;
+            let output_path = args.output.as_ref().map(|path| numbered_path(path, index, count));
+            let solution_path = args.solution_output.as_ref().map(|path| numbered_path(path, index, count));
+            let frames_dir = args.frames_dir.as_ref().map(|path| numbered_path(path, index, count));
+            if count > 1 && output_path.is_none() && index > 0 {
+                println!("\n---\n");
             }
-        }
-        
-        // Try to remove empty columns
-        let mut col = 0;
-        while col < self.width {
-            let col_empty = (0..self.height).all(|r| self.cells[r][col].is_none());
-            if col_empty {
-                for row in &mut self.cells {
-                    row.remove(col);
+            if count > 1 && !args.silent {
+                match variant_count {
+                    1 => println!("Puzzle {}/{}", index + 1, count),
+                    _ => println!("Puzzle {}/{}, variant {}/{}", index + 1, count, variant + 1, variant_count),
                 }
-                self.width -= 1;
-                changed = true;
-            } else {
-                col += 1;
             }
-        }
-        
-        changed
-    }
-
-    fn print(&self) {
-        let (min_row, max_row, min_col, max_col) = self.calculate_used_area();
-        
-        for r in min_row..=max_row {
-            for c in min_col..=max_col {
-                match self.cells[r][c] {
-                    Some(ch) => print!("{} ", ch),
-                    None => print!(". "),
-                }
+            worst_outcome = worst_outcome.max(generate_puzzle(&args, &config, word_lists.clone(), output_path.as_deref(), solution_path.as_deref(),
+                                                              frames_dir.as_deref(), variants.as_mut())?);
+            if variants.as_ref().is_some_and(|variants| variants.exhausted) {
+                break;
             }
-            println!();
         }
     }
-}
 
-struct WordSearchGenerator {
-    horizontal_words: Vec<String>,
-    vertical_words: Vec<String>,
-    silent: bool,
+    match worst_outcome {
+        Outcome::Complete => Ok(()),
+        Outcome::Partial => std::process::exit(EXIT_PARTIAL),
+        Outcome::Failed => std::process::exit(EXIT_FAILURE),
+    }
 }
 
-impl WordSearchGenerator {
-    fn new(word_lists: WordLists, silent: bool) -> Self {
-        // Sort words by length (descending) to place longer words first
-        let mut horizontal_words = word_lists.horizontal;
-        let mut vertical_words = word_lists.vertical;
-        horizontal_words.sort_by(|a, b| b.len().cmp(&a.len()));
-        vertical_words.sort_by(|a, b| b.len().cmp(&a.len()));
-        
-        Self {
-            horizontal_words,
-            vertical_words,
-            silent,
+// Append the words given on the command line, skipping any already in the list
+fn merge_words(list: &mut Vec<String>, extra: &[String]) {
+    for word in extra {
+        if !list.contains(word) {
+            list.push(word.clone());
         }
     }
+}
 
-    fn find_all_intersections(&self) -> Vec<Intersection> {
-        let mut intersections = Vec::new();
-        
-        for (h_idx, h_word) in self.horizontal_words.iter().enumerate() {
-            for (v_idx, v_word) in self.vertical_words.iter().enumerate() {
-                let h_chars: Vec<char> = h_word.chars().collect();
-                let v_chars: Vec<char> = v_word.chars().collect();
-                
-                for (h_char_idx, &h_char) in h_chars.iter().enumerate() {
-                    for (v_char_idx, &v_char) in v_chars.iter().enumerate() {
-                        if h_char == v_char {
-                            intersections.push(Intersection {
-                                h_word_idx: h_idx,
-                                v_word_idx: v_idx,
-                                h_char_idx,
-                                v_char_idx,
-                                character: h_char,
-                            });
-                        }
-                    }
-                }
-            }
-        }
-        
-        // Sort intersections by potential value (prefer common letters, center positions)
-        intersections.sort_by(|a, b| {
-            let a_score = self.score_intersection_potential(a);
-            let b_score = self.score_intersection_potential(b);
-            b_score.partial_cmp(&a_score).unwrap_or(std::cmp::Ordering::Equal)
-        });
-        
-        intersections
-    }
-
-    fn score_intersection_potential(&self, intersection: &Intersection) -> f64 {
-        let mut score = 0.0;
-        
-        // Prefer intersections with longer words
-        let h_word_len = self.horizontal_words[intersection.h_word_idx].len() as f64;
-        let v_word_len = self.vertical_words[intersection.v_word_idx].len() as f64;
-        score += (h_word_len + v_word_len) * 2.0;
-        
-        // Prefer intersections closer to word centers
-        let h_center_distance = (intersection.h_char_idx as f64 - h_word_len / 2.0).abs();
-        let v_center_distance = (intersection.v_char_idx as f64 - v_word_len / 2.0).abs();
-        score += 20.0 - (h_center_distance + v_center_distance);
-        
-        // Prefer common letters that appear in multiple words
-        let letter_frequency = self.count_letter_frequency(intersection.character);
-        score += letter_frequency as f64 * 5.0;
-        
-        score
-    }
-
-    fn count_letter_frequency(&self, letter: char) -> usize {
-        let mut count = 0;
-        for word in &self.horizontal_words {
-            count += word.chars().filter(|&c| c == letter).count();
-        }
-        for word in &self.vertical_words {
-            count += word.chars().filter(|&c| c == letter).count();
-        }
-        count
-    }
-
-    fn calculate_placement_score(&self, grid: &Grid, word: &str, row: usize, col: usize, 
-                                direction: Direction, _intersections: &[Intersection]) -> f64 {
-        let mut score = 0.0;
-        
-        // Base score - prefer central placements
-        let center_row = grid.height as f64 / 2.0;
-        let center_col = grid.width as f64 / 2.0;
-        let distance_from_center = ((row as f64 - center_row).powi(2) + 
-                                   (col as f64 - center_col).powi(2)).sqrt();
-        score += 100.0 - distance_from_center;
-        
-        // Heavily reward intersections
-        let mut intersection_count = 0;
-        let chars: Vec<char> = word.chars().collect();
-        
-        match direction {
-            Direction::Horizontal => {
-                let start_col = col + 1 - chars.len();
-                for (i, &ch) in chars.iter().enumerate() {
-                    let c = start_col + i;
-                    if let Some(existing) = grid.cells[row][c] {
-                        if existing == ch {
-                            intersection_count += 1;
-                            score += 50.0; // Large bonus for each intersection
-                        }
-                    }
-                }
-            }
-            Direction::Vertical => {
-                let start_row = row + 1 - chars.len();
-                for (i, &ch) in chars.iter().enumerate() {
-                    let r = start_row + i;
-                    if let Some(existing) = grid.cells[r][col] {
-                        if existing == ch {
-                            intersection_count += 1;
-                            score += 50.0; // Large bonus for each intersection
-                        }
-                    }
-                }
-            }
-        }
-        
-        // Bonus for word length (longer words get priority)
-        score += word.len() as f64 * 2.0;
-        
-        // Bonus for creating more future intersection opportunities
-        score += intersection_count as f64 * 25.0;
-        
-        score
-    }
-
-    fn estimate_grid_size(&self) -> (usize, usize) {
-        let max_h_len = self.horizontal_words.iter().map(|w| w.len()).max().unwrap_or(0);
-        let max_v_len = self.vertical_words.iter().map(|w| w.len()).max().unwrap_or(0);
-        
-        // More conservative estimation - account for potential intersections
-        let h_chars: usize = self.horizontal_words.iter().map(|w| w.len()).sum();
-        let v_chars: usize = self.vertical_words.iter().map(|w| w.len()).sum();
-        
-        // Assume 10-20% overlap from intersections
-        let total_chars = h_chars + v_chars;
-        let overlap_factor = 0.85; // Expect 15% reduction from intersections
-        let estimated_area = (total_chars as f64 * overlap_factor) as usize;
-        let estimated_side = (estimated_area as f64).sqrt() as usize;
-        
-        // Ensure grid can fit the longest words
-        let min_width = max_h_len.max(self.vertical_words.len()).max(10);
-        let min_height = max_v_len.max(self.horizontal_words.len()).max(10);
-        
-        let width = estimated_side.max(min_width);
-        let height = estimated_side.max(min_height);
-        
-        (width, height)
-    }
-
-
-    fn generate_candidates(&self, grid: &Grid, word: &str, direction: Direction, 
-                          intersections: &[Intersection]) -> Vec<PlacementCandidate> {
-        let mut candidates = Vec::new();
-        
-        match direction {
-            Direction::Horizontal => {
-                for row in 0..grid.height {
-                    for col in (word.len()-1)..grid.width {
-                        if grid.can_place_word(word, row, col, direction) {
-                            let score = self.calculate_placement_score(grid, word, row, col, direction, intersections);
-                            candidates.push(PlacementCandidate {
-                                word_idx: 0, // Will be set by caller
-                                direction,
-                                row,
-                                col,
-                                score,
-                                intersections: Vec::new(), // Will be computed later if needed
-                            });
-                        }
-                    }
-                }
-            }
-            Direction::Vertical => {
-                for row in (word.len()-1)..grid.height {
-                    for col in 0..grid.width {
-                        if grid.can_place_word(word, row, col, direction) {
-                            let score = self.calculate_placement_score(grid, word, row, col, direction, intersections);
-                            candidates.push(PlacementCandidate {
-                                word_idx: 0, // Will be set by caller
-                                direction,
-                                row,
-                                col,
-                                score,
-                                intersections: Vec::new(),
-                            });
-                        }
-                    }
-                }
-            }
-        }
-        
-        // Sort by score (highest first)
-        candidates.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
-        
-        // Take top candidates to avoid exponential explosion
-        candidates.truncate(50);
-        candidates
-    }
-
-    fn generate_intersection_first(&self, width: usize, height: usize, max_attempts: usize) -> Option<(Grid, Vec<PlacedWord>)> {
-        let intersections = self.find_all_intersections();
-        let mut best_solution: Option<(Grid, Vec<PlacedWord>)> = None;
-        let mut best_score = f64::NEG_INFINITY;
-
-        if !self.silent {
-            println!("Intersection-first algorithm: {} intersections", intersections.len());
+// Generation settings: those in the --config file (the defaults without one), with each
+// option given on the command line taking precedence
+fn generation_config(args: &Args, matches: &ArgMatches) -> Result<GenerationConfig, String> {
+    let mut config = match &args.config {
+        Some(path) => {
+            let yaml = fs::read_to_string(path).map_err(|e| format!("cannot read {}: {}", path.display(), e))?;
+            serde_yaml::from_str(&yaml).map_err(|e| format!("invalid config file {}: {}", path.display(), e))?
         }
+        None => GenerationConfig::default(),
+    };
+    // Options with a default value only override the file when actually given
+    let given = |id: &str| matches.value_source(id) == Some(ValueSource::CommandLine);
 
-        for attempt in 0..max_attempts {
-            if !self.silent && attempt % 25 == 0 {
-                println!("Intersection-first attempt {}/{}", attempt + 1, max_attempts);
-            }
-
-            let mut grid = Grid::new(width, height);
-            let mut placed_words = Vec::new();
-            let mut used_h_words = vec![false; self.horizontal_words.len()];
-            let mut used_v_words = vec![false; self.vertical_words.len()];
-            let mut rng = rand::thread_rng();
-
-            // Phase 1: Force high-value intersections
-            let mut intersections_copy = intersections.clone();
-            intersections_copy.shuffle(&mut rng);
-            
-            let mut forced_intersections = 0;
-            for intersection in intersections_copy.iter().take(3) { // Try top 3 intersections
-                if used_h_words[intersection.h_word_idx] || used_v_words[intersection.v_word_idx] {
-                    continue;
-                }
-
-                let h_word = &self.horizontal_words[intersection.h_word_idx];
-                let v_word = &self.vertical_words[intersection.v_word_idx];
-
-                // Try to place both words at their intersection
-                let center_row = height / 2;
-                let center_col = width / 2;
-                
-                // Calculate positions for intersection
-                let h_row = center_row;
-                let h_col = center_col + intersection.h_char_idx;
-                let v_row = center_row + intersection.v_char_idx;
-                let v_col = center_col;
-
-                if h_col < width && v_row < height &&
-                   grid.can_place_word(h_word, h_row, h_col, Direction::Horizontal) &&
-                   grid.can_place_word(v_word, v_row, v_col, Direction::Vertical) {
-                    
-                    grid.place_word(h_word, h_row, h_col, Direction::Horizontal);
-                    grid.place_word(v_word, v_row, v_col, Direction::Vertical);
-                    
-                    placed_words.push(PlacedWord {
-                        word: h_word.clone(),
-                        start_row: h_row,
-                        start_col: h_col + 1 - h_word.len(),
-                        direction: Direction::Horizontal,
-                    });
-                    
-                    placed_words.push(PlacedWord {
-                        word: v_word.clone(),
-                        start_row: v_row + 1 - v_word.len(),
-                        start_col: v_col,
-                        direction: Direction::Vertical,
-                    });
-                    
-                    used_h_words[intersection.h_word_idx] = true;
-                    used_v_words[intersection.v_word_idx] = true;
-                    forced_intersections += 1;
-                }
-            }
+    if args.seed.is_some() {
+        config.seed = args.seed;
+    }
+    if args.width.is_some() {
+        config.fixed_width = args.width;
+    }
+    if args.height.is_some() {
+        config.fixed_height = args.height;
+    }
+    config.auto_grow |= args.auto_grow;
+    if given("max_grow") {
+        config.max_grow = args.max_grow;
+    }
+    if given("placement_order") {
+        config.placement_order = args.placement_order;
+    }
+    if given("interleave") {
+        config.queue_interleave = args.interleave;
+    }
+    config.strict_separation |= args.strict_separation;
+    config.valid_crossword |= args.valid_crossword;
+    config.forbid_nested_words |= args.no_nested_words;
+    if args.max_intersections_per_word.is_some() {
+        config.max_intersections_per_word = args.max_intersections_per_word;
+    }
+    config.all_words_connected |= args.all_words_connected;
+    config.spread |= args.spread;
+    config.include_reversed |= args.include_reversed;
+    config.rare_letter_bonus |= args.rare_letter_bonus;
+    if let Some(letters) = &args.prefer_intersection_letters {
+        config.preferred_intersection_letters = letters.chars()
+            .filter(|ch| !ch.is_whitespace() && *ch != ',')
+            .flat_map(char::to_lowercase)
+            .collect();
+    }
+    if args.algorithm.is_some() {
+        config.algorithm = args.algorithm;
+    }
+    if args.shape.is_some() {
+        config.shape = args.shape;
+    }
+    if args.reserve.is_some() {
+        config.reserved = args.reserve;
+    }
+    if given("orient") {
+        config.orientation = args.orient;
+    }
+    config.transpose |= args.transpose;
+    if given("reading_order") {
+        config.reading_order = args.reading_order;
+    }
+    if given("margin") {
+        config.margin = args.margin;
+    }
+    if given("prefer") {
+        config.preference = args.prefer;
+    }
+    if args.prefer_width {
+        config.growth_bias = Some(GrowthBias::Wide);
+    } else if args.prefer_height {
+        config.growth_bias = Some(GrowthBias::Tall);
+    }
+    if args.max_aspect.is_some() {
+        config.max_aspect = args.max_aspect;
+    }
+    if given("placement_tries") {
+        config.placement_tries = args.placement_tries;
+    }
+    if given("size_factor") {
+        config.size_factor = args.size_factor;
+    }
+    config.skip_compaction |= args.no_compact;
+    if let Some(top) = args.top {
+        config.top = top as usize;
+    }
+    if args.good_enough_area.is_some() {
+        config.good_enough_area = args.good_enough_area;
+    }
+    if args.low_memory {
+        config.max_intersections_considered = LOW_MEMORY_INTERSECTIONS;
+        config.max_candidates = LOW_MEMORY_CANDIDATES;
+    }
+    if let Some(intersections) = args.max_intersections_considered {
+        config.max_intersections_considered = intersections;
+    }
+    if let Some(candidates) = args.max_candidates {
+        config.max_candidates = candidates;
+    }
+    if args.max_positions_scored.is_some() {
+        config.max_positions_scored = args.max_positions_scored;
+    }
+    if given("greediness") {
+        config.greediness = args.greediness;
+    }
+    if given("anneal_iterations") {
+        config.anneal_iterations = args.anneal_iterations;
+    }
+    if args.no_anneal {
+        config.anneal_iterations = 0;
+    }
+    if given("sa_initial_temp") {
+        config.anneal_initial_temperature = args.sa_initial_temp;
+    }
+    if given("sa_cooling_rate") {
+        config.anneal_cooling_rate = args.sa_cooling_rate;
+    }
+    config.flexible_directions |= args.flexible_directions;
+    if args.blank_bank.is_some() {
+        config.blank_bank = args.blank_bank;
+    }
+    Ok(config)
+}
 
-            // Phase 2: Place remaining words optimally
-            let mut success = true;
-            
-            // Place remaining horizontal words
-            for (h_idx, h_word) in self.horizontal_words.iter().enumerate() {
-                if used_h_words[h_idx] { continue; }
-                
-                let candidates = self.generate_candidates(&grid, h_word, Direction::Horizontal, &intersections);
-                let mut placed = false;
-                
-                for candidate in candidates.iter().take(5) {
-                    if grid.place_word(h_word, candidate.row, candidate.col, candidate.direction) {
-                        placed_words.push(PlacedWord {
-                            word: h_word.clone(),
-                            start_row: candidate.row,
-                            start_col: candidate.col + 1 - h_word.len(),
-                            direction: Direction::Horizontal,
-                        });
-                        placed = true;
-                        break;
-                    }
-                }
-                
-                if !placed {
-                    success = false;
-                    break;
-                }
-            }
+// Apply the generation options shared by every run of the generator
+fn configure_generator(generator: &mut WordSearchGenerator, args: &Args, config: &GenerationConfig) {
+    generator.quiet_progress = args.quiet_progress;
+    generator.set_config(config.clone());
+}
 
-            // Place remaining vertical words
-            if success {
-                for (v_idx, v_word) in self.vertical_words.iter().enumerate() {
-                    if used_v_words[v_idx] { continue; }
-                    
-                    let candidates = self.generate_candidates(&grid, v_word, Direction::Vertical, &intersections);
-                    let mut placed = false;
-                    
-                    for candidate in candidates.iter().take(5) {
-                        if grid.place_word(v_word, candidate.row, candidate.col, candidate.direction) {
-                            placed_words.push(PlacedWord {
-                                word: v_word.clone(),
-                                start_row: candidate.row + 1 - v_word.len(),
-                                start_col: candidate.col,
-                                direction: Direction::Vertical,
-                            });
-                            placed = true;
-                            break;
-                        }
-                    }
-                    
-                    if !placed {
-                        success = false;
-                        break;
-                    }
-                }
-            }
+// Generate with seeds 0..seeds in parallel and return the seed of the best result:
+// the most words placed, then the smallest area, then the most crossings, then the
+// lowest seed
+fn sweep_seeds(args: &Args, config: &GenerationConfig, word_lists: &WordLists, seeds: u64) -> Option<u64> {
+    (0..seeds).into_par_iter()
+        .filter_map(|seed| {
+            let mut generator = WordSearchGenerator::new(word_lists.clone(), true);
+            configure_generator(&mut generator, args, &GenerationConfig { seed: Some(seed), ..config.clone() });
+            let (grid, placed_words) = generator.generate(args.max_attempts)?;
+            let (height, width) = grid.get_used_dimensions();
+            let rank = (placed_words.len(), Reverse(height * width), count_crossings(&placed_words), Reverse(seed));
+            Some((rank, seed))
+        })
+        .max()
+        .map(|(_, seed)| seed)
+}
 
-            if success {
-                let (used_height, used_width) = grid.get_used_dimensions();
-                let area = used_height * used_width;
-                
-                // Enhanced scoring for intersection-first approach
-                let compactness_score = 2000.0 / (area as f64);
-                let square_diff = (used_height as i32 - used_width as i32).abs() as f64;
-                let squareness_score = 200.0 / (1.0 + square_diff);
-                let intersection_bonus = (forced_intersections + self.count_total_intersections(&grid, &placed_words)) as f64 * 25.0;
-                
-                let total_score = compactness_score + squareness_score + intersection_bonus;
-                
-                if total_score > best_score {
-                    best_score = total_score;
-                    best_solution = Some((grid, placed_words));
-                    
-                    if !self.silent {
-                        println!("Intersection-first solution: area {} ({}x{}), intersections: {}, score: {:.2}", 
-                                area, used_height, used_width, forced_intersections, total_score);
-                    }
-                }
-            }
+// Generate, report and output a single puzzle
+fn generate_puzzle(args: &Args, config: &GenerationConfig, mut word_lists: WordLists, output_path: Option<&Path>, solution_path: Option<&Path>, frames_dir: Option<&Path>,
+                   mut variants: Option<&mut Variants>)
+    -> Result<Outcome, Box<dyn std::error::Error>> {
+    // Share a single `words` list between the two directions and show how it was split
+    if !word_lists.words.is_empty() {
+        let count = word_lists.words.len();
+        word_lists.split_words();
+        if !args.silent {
+            let letters = |list: &[String]| list.iter().map(|word| strip_separators(word).chars().count()).sum::<usize>();
+            println!("Split {} words: horizontal {} ({} letters); vertical {} ({} letters)", count,
+                     word_lists.horizontal.join(", "), letters(&word_lists.horizontal),
+                     word_lists.vertical.join(", "), letters(&word_lists.vertical));
         }
-
-        best_solution
     }
 
-    fn count_total_intersections(&self, grid: &Grid, placed_words: &[PlacedWord]) -> usize {
-        placed_words.iter()
-            .map(|word| self.count_intersections(grid, word))
-            .sum()
+    // Validate input
+    if word_lists.horizontal.is_empty() && word_lists.vertical.is_empty() && word_lists.pinned.is_empty() {
+        eprintln!("Error: No words provided in input file");
+        std::process::exit(EXIT_INVALID_INPUT);
     }
 
-    fn generate_simulated_annealing(&self, initial_solution: (Grid, Vec<PlacedWord>), iterations: usize) -> (Grid, Vec<PlacedWord>) {
-        let mut current_solution = initial_solution;
-        let mut best_solution = current_solution.clone();
-        let mut best_score = self.evaluate_solution(&current_solution.0, &current_solution.1);
-        let mut temperature = 1000.0;
-        let cooling_rate = 0.95;
-        let mut rng = rand::thread_rng();
-
-        if !self.silent {
-            println!("Starting simulated annealing with {} iterations", iterations);
-        }
-
-        for iteration in 0..iterations {
-            if iteration % 50 == 0 {
-                temperature *= cooling_rate;
+    for (first, second) in &word_lists.forbidden_crossings {
+        for word in [first, second] {
+            if !word_lists.horizontal.contains(word) && !word_lists.vertical.contains(word) && !word_lists.pinned.contains_key(word) {
+                eprintln!("Warning: forbidden crossing {} / {} refers to {}, which is not in the word lists", first, second, word);
             }
+        }
+    }
 
-            // Generate a neighbor solution by slightly moving one word
-            let mut new_solution = current_solution.clone();
-            if self.try_optimize_single_word(&mut new_solution.0, &mut new_solution.1, &mut rng) {
-                let new_score = self.evaluate_solution(&new_solution.0, &new_solution.1);
-                let current_score = self.evaluate_solution(&current_solution.0, &current_solution.1);
-                
-                // Accept if better, or with probability if worse
-                let delta = new_score - current_score;
-                if delta > 0.0 || rng.gen::<f64>() < (delta / temperature).exp() {
-                    current_solution = new_solution;
-                    
-                    if new_score > best_score {
-                        best_score = new_score;
-                        best_solution = current_solution.clone();
-                        
-                        if !self.silent && iteration % 100 == 0 {
-                            let (h, w) = best_solution.0.get_used_dimensions();
-                            println!("SA iteration {}: new best area {} ({}x{}), score: {:.2}", 
-                                   iteration, h * w, h, w, best_score);
-                        }
-                    }
-                }
+    for (label, words) in &word_lists.groups {
+        for word in words {
+            if !word_lists.horizontal.contains(word) && !word_lists.vertical.contains(word) && !word_lists.pinned.contains_key(word) {
+                eprintln!("Warning: group {} lists {}, which is not in the word lists", label, word);
             }
         }
+    }
 
-        best_solution
-    }
-
-    fn try_optimize_single_word(&self, grid: &mut Grid, placed_words: &mut Vec<PlacedWord>, rng: &mut impl Rng) -> bool {
-        if placed_words.is_empty() { return false; }
-        
-        let word_idx = rng.gen_range(0..placed_words.len());
-        let word = &placed_words[word_idx];
-        
-        // Remove the word temporarily
-        self.remove_word_from_grid(grid, word);
-        let removed_word = placed_words.remove(word_idx);
-        
-        // Try to place it in a better position
-        let candidates = self.generate_candidates(grid, &removed_word.word, removed_word.direction, &[]);
-        
-        for candidate in candidates.iter().take(5) {
-            if grid.place_word(&removed_word.word, candidate.row, candidate.col, candidate.direction) {
-                placed_words.push(PlacedWord {
-                    word: removed_word.word.clone(),
-                    start_row: match candidate.direction {
-                        Direction::Horizontal => candidate.row,
-                        Direction::Vertical => candidate.row + 1 - removed_word.word.len(),
-                    },
-                    start_col: match candidate.direction {
-                        Direction::Horizontal => candidate.col + 1 - removed_word.word.len(),
-                        Direction::Vertical => candidate.col,
-                    },
-                    direction: candidate.direction,
-                });
-                return true;
-            }
+    let title = word_lists.title.clone();
+    let instructions = word_lists.instructions.clone();
+
+    // Only colorize when explicitly requested, writing to a terminal and NO_COLOR is unset
+    let style = RenderStyle {
+        color: args.color && output_path.is_none() && color_allowed(),
+        output_case: args.output_case,
+        coords: args.coords,
+        empty_char: args.empty_char,
+        answer_key: args.answer_key,
+        answer_key_style: args.answer_key_style,
+        origin: args.origin,
+        sections: if args.grid_only {
+            Sections::Grid
+        } else if args.solution_only {
+            Sections::Solution
+        } else {
+            Sections::All
+        },
+    };
+
+    // Create generator and generate puzzle
+    let sweep_lists = args.seed_sweep.map(|_| word_lists.clone());
+    let mut generator = match WordSearchGenerator::try_new(word_lists, args.silent) {
+        Ok(generator) => generator,
+        Err(message) => {
+            eprintln!("Error: {}, which cannot be placed", message);
+            std::process::exit(EXIT_INVALID_INPUT);
         }
-        
-        // If no better position found, put it back in original position
-        if grid.place_word(&removed_word.word, 
-                          match removed_word.direction {
-                              Direction::Horizontal => removed_word.start_row,
-                              Direction::Vertical => removed_word.start_row + removed_word.word.len() - 1,
-                          },
-                          match removed_word.direction {
-                              Direction::Horizontal => removed_word.start_col + removed_word.word.len() - 1,
-                              Direction::Vertical => removed_word.start_col,
-                          },
-                          removed_word.direction) {
-            placed_words.push(removed_word);
+    };
+    // Configured first so the check sees --flexible-directions
+    configure_generator(&mut generator, args, config);
+    let impossible = generator.impossible_crossings();
+    if !impossible.is_empty() {
+        for (first, second) in impossible {
+            eprintln!("Error: required crossing {} / {} cannot be met; both words must be in the lists, run in different directions (or be free to turn with --flexible-directions) and share a letter", first, second);
         }
-        
-        false
-    }
-
-    fn remove_word_from_grid(&self, grid: &mut Grid, word: &PlacedWord) {
-        let chars: Vec<char> = word.word.chars().collect();
-        
-        match word.direction {
-            Direction::Horizontal => {
-                for (i, _) in chars.iter().enumerate() {
-                    let col = word.start_col + i;
-                    if col < grid.width {
-                        grid.cells[word.start_row][col] = None;
-                    }
-                }
-            }
-            Direction::Vertical => {
-                for (i, _) in chars.iter().enumerate() {
-                    let row = word.start_row + i;
-                    if row < grid.height {
-                        grid.cells[row][word.start_col] = None;
-                    }
-                }
-            }
+        std::process::exit(EXIT_INVALID_INPUT);
+    }
+
+    if args.analyze {
+        let analysis = generator.analyze();
+        match args.format {
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&analysis)?),
+            _ => print_analysis(&analysis),
         }
+        return Ok(Outcome::Complete);
     }
 
-    fn evaluate_solution(&self, grid: &Grid, placed_words: &[PlacedWord]) -> f64 {
-        let (used_height, used_width) = grid.get_used_dimensions();
-        let area = used_height * used_width;
-        let compactness_score = 2000.0 / (area as f64);
-        let square_diff = (used_height as i32 - used_width as i32).abs() as f64;
-        let squareness_score = 200.0 / (1.0 + square_diff);
-        let intersection_count = self.count_total_intersections(grid, placed_words);
-        let intersection_bonus = intersection_count as f64 * 25.0;
-        
-        compactness_score + squareness_score + intersection_bonus
+    // Load the comparison puzzle up front so a bad path fails before generating
+    let diff_snapshot: Option<PuzzleSnapshot> = args.diff.as_ref().map(|path| {
+        fs::read_to_string(path)
+            .map_err(|e| e.to_string())
+            .and_then(|json| serde_json::from_str(&json).map_err(|e| e.to_string()))
+            .unwrap_or_else(|e| {
+                eprintln!("Error: cannot load {} for --diff: {}", path.display(), e);
+                std::process::exit(EXIT_INVALID_INPUT);
+            })
+    });
+
+    let pin_conflicts = generator.pin_conflicts();
+    if !pin_conflicts.is_empty() {
+        eprintln!("Error: these pinned words conflict with other pinned words or the placement rules: {:?}", pin_conflicts);
+        std::process::exit(EXIT_INVALID_INPUT);
     }
 
-    fn generate_optimized(&self, width: usize, height: usize, max_attempts: usize) -> Option<(Grid, Vec<PlacedWord>)> {
-        let intersections = self.find_all_intersections();
-        let mut best_solution: Option<(Grid, Vec<PlacedWord>)> = None;
-        let mut best_score = f64::NEG_INFINITY;
+    let too_long = generator.words_too_long();
+    if !too_long.is_empty() {
+        eprintln!("Error: these words are longer than the fixed grid size{} allows: {:?}",
+                  if config.shape.is_some() { " and shape" } else { "" }, too_long);
+        std::process::exit(EXIT_INVALID_INPUT);
+    }
+
+    if !generator.trimmed_words().is_empty() {
+        eprintln!("Warning: removed leading/trailing whitespace from {:?}", generator.trimmed_words());
+    }
 
-        if !self.silent {
-            println!("Found {} potential intersections", intersections.len());
+    if let Some(max_distance) = args.warn_similar {
+        for (a, b, distance) in generator.similar_words(max_distance) {
+            eprintln!("Warning: {} and {} are similar (edit distance {})", a, b, distance);
         }
+    }
 
-        for attempt in 0..max_attempts {
-            if !self.silent && attempt % 50 == 0 {
-                println!("Optimization attempt {}/{}", attempt + 1, max_attempts);
+    // Words that cannot cross anything also make --all-words-connected impossible
+    let disconnected = generator.disconnected_words();
+    if !disconnected.is_empty() {
+        if args.require_connected || config.all_words_connected {
+            eprintln!("Error: these words share no letters with any word in the other direction: {:?}", disconnected);
+            std::process::exit(EXIT_INVALID_INPUT);
+        }
+        eprintln!("Warning: these words share no letters with any word in the other direction and will never cross another word: {:?}", disconnected);
+    }
+    
+    // Find the best seed, then generate with it below so the output matches --seed
+    if let (Some(seeds), Some(word_lists)) = (args.seed_sweep, sweep_lists) {
+        match sweep_seeds(args, config, &word_lists, seeds) {
+            Some(seed) => {
+                eprintln!("Seed sweep: seed {} gave the best of {} seeds (reproduce with --seed {})", seed, seeds, seed);
+                generator.set_config(GenerationConfig { seed: Some(seed), ..generator.config().clone() });
+            }
+            None => {
+                eprintln!("Seed sweep: no seed produced a solution");
+                return Ok(Outcome::Failed);
             }
+        }
+    }
 
-            let mut grid = Grid::new(width, height);
-            let mut placed_words = Vec::new();
-            let mut remaining_h: Vec<_> = (0..self.horizontal_words.len()).collect();
-            let mut remaining_v: Vec<_> = (0..self.vertical_words.len()).collect();
-            let mut rng = rand::thread_rng();
-            
-            // Shuffle to try different orderings
-            remaining_h.shuffle(&mut rng);
-            remaining_v.shuffle(&mut rng);
-
-            let mut success = true;
-            let mut placement_queue = VecDeque::new();
-            
-            // Build initial placement queue with alternating word types for better intersection opportunities
-            let mut h_iter = remaining_h.iter();
-            let mut v_iter = remaining_v.iter();
-            
-            loop {
-                match (h_iter.next(), v_iter.next()) {
-                    (Some(&h_idx), Some(&v_idx)) => {
-                        placement_queue.push_back((h_idx, Direction::Horizontal));
-                        placement_queue.push_back((v_idx, Direction::Vertical));
-                    }
-                    (Some(&h_idx), None) => {
-                        placement_queue.push_back((h_idx, Direction::Horizontal));
-                    }
-                    (None, Some(&v_idx)) => {
-                        placement_queue.push_back((v_idx, Direction::Vertical));
-                    }
-                    (None, None) => break,
+    let result = if let Some(variants) = variants.as_deref_mut() {
+        generate_variant(args, &mut generator, &style, variants)
+    } else if args.live_preview {
+        let preview_style = style.clone();
+        generator.generate_streaming(args.max_attempts, move |update| {
+            println!("\nNew best {} solution (score: {:.2}):", update.stage, update.score);
+            update.grid.print(update.placed_words, &preview_style);
+        })
+    } else {
+        generator.generate(args.max_attempts)
+    };
+
+    match result {
+        Some((grid, placed_words)) => {
+            if args.verify {
+                let misplaced = grid.misplaced_words(&placed_words);
+                if !misplaced.is_empty() {
+                    let words: Vec<&str> = misplaced.iter().map(|word| word.display_word()).collect();
+                    eprintln!("Error: these words do not read correctly from their recorded positions: {:?}", words);
+                    return Ok(Outcome::Failed);
                 }
             }
-
-            // Place words using intelligent candidate selection
-            while let Some((word_idx, direction)) = placement_queue.pop_front() {
-                let word = match direction {
-                    Direction::Horizontal => &self.horizontal_words[word_idx],
-                    Direction::Vertical => &self.vertical_words[word_idx],
-                };
-
-                let candidates = self.generate_candidates(&grid, word, direction, &intersections);
-                
-                let mut placed = false;
-                // Try the best candidates first, with some randomization
-                let try_count = (candidates.len().min(10)).max(1);
-                for i in 0..try_count {
-                    let candidate_idx = if i < 3 { i } else { rng.gen_range(0..candidates.len()) };
-                    if let Some(candidate) = candidates.get(candidate_idx) {
-                        if grid.place_word(word, candidate.row, candidate.col, candidate.direction) {
-                            placed_words.push(PlacedWord {
-                                word: word.clone(),
-                                start_row: match candidate.direction {
-                                    Direction::Horizontal => candidate.row,
-                                    Direction::Vertical => candidate.row + 1 - word.len(),
-                                },
-                                start_col: match candidate.direction {
-                                    Direction::Horizontal => candidate.col + 1 - word.len(),
-                                    Direction::Vertical => candidate.col,
-                                },
-                                direction: candidate.direction,
-                            });
-                            placed = true;
-                            break;
-                        }
+            if !args.silent {
+                println!("\nSuccessfully generated word search!");
+                print_timings(&generator.stats());
+                println!("\nPlaced words:");
+                for word in &placed_words {
+                    let (row, col) = grid.first_letter_cell(word, style.origin);
+                    println!("  {} ({:?}{}{}) at ({}, {})",
+                             word.display_word(), word.direction, if word.reversed { ", reversed" } else { "" },
+                             if word.mirrored { ", hidden reverse copy" } else { "" }, row, col);
+                }
+                for (first, second, cell) in generator.required_crossing_cells(&placed_words) {
+                    if let Some(cell) = cell {
+                        let (row, col) = grid.user_cell(cell, style.origin);
+                        println!("  {} crosses {} at ({}, {})", first, second, row, col);
                     }
                 }
-
-                if !placed {
-                    success = false;
-                    break;
+                if args.trace_placements {
+                    print_placement_trace(&grid, &placed_words, &style);
                 }
+                println!("\nGrid:");
             }
-
-            if success {
-                let (used_height, used_width) = grid.get_used_dimensions();
-                let area = used_height * used_width;
-                let square_diff = (used_height as i32 - used_width as i32).abs() as f64;
-                
-                // Enhanced scoring that heavily favors compactness and squareness
-                let compactness_score = 1000.0 / (area as f64);
-                let squareness_score = 100.0 / (1.0 + square_diff);
-                let intersection_bonus = placed_words.iter()
-                    .map(|word| self.count_intersections(&grid, word))
-                    .sum::<usize>() as f64 * 10.0;
-                
-                let total_score = compactness_score + squareness_score + intersection_bonus;
-                
-                if total_score > best_score {
-                    best_score = total_score;
-                    best_solution = Some((grid, placed_words));
-                    
-                    if !self.silent {
-                        println!("Found optimized solution: area {} ({}x{}), score: {:.2}", 
-                                area, used_height, used_width, total_score);
-                    }
+            // With --top, each solution gets its own numbered output and answer key files.
+            // On standard output JSON solutions are gathered into one array instead
+            let alternatives = generator.alternatives();
+            let solutions = alternatives.len() + 1;
+            let option_path = |path: Option<&Path>, index| path.map(|path| numbered_path(path, index, solutions));
+            let json_array = matches!(args.format, OutputFormat::Json) && output_path.is_none() && solutions > 1;
+            let mut json_documents = Vec::new();
+            let mut emit = |document: Vec<u8>| -> std::io::Result<()> {
+                if json_array {
+                    json_documents.push(String::from_utf8_lossy(&document).trim_end().to_string());
+                    Ok(())
+                } else {
+                    std::io::stdout().write_all(&document)
                 }
+            };
+            if let Some(document) = write_puzzle(args, &generator, &grid, &placed_words, &style, title.as_deref(), instructions.as_deref(),
+                                                 option_path(output_path, 0).as_deref(), option_path(solution_path, 0).as_deref())? {
+                emit(document)?;
             }
-        }
-
-        best_solution
-    }
-
-    fn count_intersections(&self, grid: &Grid, word: &PlacedWord) -> usize {
-        let mut count = 0;
-        let chars: Vec<char> = word.word.chars().collect();
-        
-        match word.direction {
-            Direction::Horizontal => {
-                for (i, _) in chars.iter().enumerate() {
-                    let col = word.start_col + i;
-                    if let Some(_) = grid.cells[word.start_row][col] {
-                        // Check if there's a vertical word crossing here
-                        for r in 0..grid.height {
-                            if r != word.start_row && grid.cells[r][col].is_some() {
-                                count += 1;
-                                break;
-                            }
-                        }
-                    }
+            if let Some(dir) = frames_dir {
+                write_placement_frames(dir, &grid, &placed_words, &style)?;
+                if !args.silent {
+                    println!("Wrote {} placement frames to {}", placed_words.len(), dir.display());
                 }
             }
-            Direction::Vertical => {
-                for (i, _) in chars.iter().enumerate() {
-                    let row = word.start_row + i;
-                    if let Some(_) = grid.cells[row][word.start_col] {
-                        // Check if there's a horizontal word crossing here
-                        for c in 0..grid.width {
-                            if c != word.start_col && grid.cells[row][c].is_some() {
-                                count += 1;
-                                break;
-                            }
-                        }
-                    }
-                }
+            if !args.silent {
+                print_summary(&grid, &placed_words, &generator.stats());
             }
-        }
-        
-        count
-    }
-
-    fn generate_with_size(&self, width: usize, height: usize, max_attempts: usize) -> Option<(Grid, Vec<PlacedWord>)> {
-        let mut rng = rand::thread_rng();
-        let mut best_solution: Option<(Grid, Vec<PlacedWord>)> = None;
-        let mut best_area = usize::MAX;
-
-        for attempt in 0..max_attempts {
-            if !self.silent && attempt % 100 == 0 {
-                println!("Attempt {}/{}", attempt + 1, max_attempts);
+            if let (Some(path), Some(old)) = (&args.diff, &diff_snapshot) {
+                let plain_style = RenderStyle { color: false, coords: false, ..style.clone() };
+                let export = grid.export(&placed_words, &plain_style, None, None, None);
+                print_diff(path, &export, old);
             }
-
-            let mut grid = Grid::new(width, height);
-            let mut placed_words = Vec::new();
-            let mut remaining_h = self.horizontal_words.clone();
-            let mut remaining_v = self.vertical_words.clone();
-            
-            remaining_h.shuffle(&mut rng);
-            remaining_v.shuffle(&mut rng);
-
-            // Try to place all words
-            let mut success = true;
-            
-            // Place horizontal words (now sorted by length, longest first)
-            for word in &remaining_h {
-                let mut placed = false;
-                let mut attempts = 0;
-                
-                // First try to find good placement considering existing vertical words
-                while !placed && attempts < 150 {
-                    let row = rng.gen_range(0..height);
-                    let col = rng.gen_range(word.len()-1..width);
-                    
-                    if grid.place_word(word, row, col, Direction::Horizontal) {
-                        placed_words.push(PlacedWord {
-                            word: word.clone(),
-                            start_row: row,
-                            start_col: col + 1 - word.len(),
-                            direction: Direction::Horizontal,
-                        });
-                        placed = true;
-                    }
-                    attempts += 1;
+            for (index, (grid, placed_words)) in (1..).zip(&alternatives) {
+                if output_path.is_none() && !json_array {
+                    println!("\n---\n");
+                }
+                if !args.silent {
+                    let (height, width) = grid.get_used_dimensions();
+                    println!("Option {}/{}: {}x{} grid, area {}, {} intersections", index + 1, solutions, height, width,
+                             height * width, count_crossings(placed_words));
                 }
-                
-                if !placed {
-                    success = false;
-                    break;
+                if let Some(document) = write_puzzle(args, &generator, grid, placed_words, &style, title.as_deref(), instructions.as_deref(),
+                                                     option_path(output_path, index).as_deref(), option_path(solution_path, index).as_deref())? {
+                    emit(document)?;
                 }
             }
-            
-            // Place vertical words (now sorted by length, longest first)
-            if success {
-                for word in &remaining_v {
-                    let mut placed = false;
-                    let mut attempts = 0;
-                    
-                    // Try to place with more attempts for better results
-                    while !placed && attempts < 150 {
-                        let row = rng.gen_range(word.len()-1..height);
-                        let col = rng.gen_range(0..width);
-                        
-                        if grid.place_word(word, row, col, Direction::Vertical) {
-                            placed_words.push(PlacedWord {
-                                word: word.clone(),
-                                start_row: row + 1 - word.len(),
-                                start_col: col,
-                                direction: Direction::Vertical,
-                            });
-                            placed = true;
+            if json_array {
+                println!("[\n{}\n]", json_documents.join(",\n"));
+            }
+            if let Some(top) = args.top.filter(|&top| top as usize > solutions) {
+                eprintln!("Note: found {} distinct solution(s) of the {} requested with --top; more --max-attempts may find others.",
+                          solutions, top);
+            }
+            let missing = generator.missing_words(&placed_words);
+            if !missing.is_empty() {
+                eprintln!("Warning: these words could not be placed: {:?}", missing);
+                if config.forbid_nested_words {
+                    for (shorter, longer) in generator.substring_words() {
+                        if missing.contains(&shorter) {
+                            eprintln!("{} is part of {} and --no-nested-words needs it placed apart from it; a larger grid may help.", shorter, longer);
                         }
-                        attempts += 1;
-                    }
-                    
-                    if !placed {
-                        success = false;
-                        break;
                     }
                 }
+                return Ok(Outcome::Partial);
             }
-
-            if success {
-                let (used_height, used_width) = grid.get_used_dimensions();
-                let area = used_height * used_width;
-                let square_diff = (used_height as i32 - used_width as i32).abs() as usize;
-                
-                // Improved scoring: heavily weight area reduction, moderately weight squareness
-                let score = area * 10 + square_diff * 3;
-                
-                if score < best_area {
-                    best_area = score;
-                    best_solution = Some((grid, placed_words));
-                    
-                    if !self.silent {
-                        println!("Found solution with area {} ({}x{}), score: {}", area, used_height, used_width, score);
-                    }
+        }
+        None if variants.as_ref().is_some_and(|variants| variants.exhausted) => {
+            eprintln!("Error: found only {} layout(s) differing in at least {:.0}% of cells of the {} requested; a lower --variant-difference or a larger --size-factor may help.",
+                      variants.map_or(0, |variants| variants.earlier.len()), args.variant_difference * 100.0, args.variants.unwrap_or(1));
+            return Ok(Outcome::Partial);
+        }
+        None => {
+            eprintln!("Failed to generate word search puzzle. Try increasing --max-attempts or using shorter words.");
+            if let Some(shape) = config.shape {
+                eprintln!("The words may not fit the {} shape; a larger --size-factor (or --width/--height) gives them more room.",
+                          format!("{:?}", shape).to_lowercase());
+            }
+            if config.forbid_nested_words {
+                for (shorter, longer) in generator.substring_words() {
+                    eprintln!("{} is part of {}; --no-nested-words needs it placed apart from it, which may have made the puzzle infeasible.", shorter, longer);
                 }
             }
+            if !generator.forbidden_crossings.is_empty() {
+                eprintln!("The {} forbidden crossing(s) in the input may have made the puzzle infeasible.",
+                          generator.forbidden_crossings.len());
+            }
+            if !generator.required_crossings.is_empty() {
+                eprintln!("The {} required crossing(s) in the input may have made the puzzle infeasible.",
+                          generator.required_crossings.len());
+            }
+            if config.valid_crossword {
+                eprintln!("--valid-crossword rejects any layout where words touch side by side, which may have made the puzzle infeasible; a larger --size-factor gives words more room.");
+            }
+            if let (Some(max_aspect), Some(aspect)) = (config.max_aspect, generator.stats().best_rejected_aspect) {
+                eprintln!("The squarest solution found had an aspect ratio of {:.2}, above --max-aspect {}.", aspect, max_aspect);
+            }
+            let floating_words = generator.stats().floating_words;
+            if !floating_words.is_empty() {
+                eprintln!("The closest rejected solution left these words without a crossing: {:?}", floating_words);
+            }
+            return Ok(Outcome::Failed);
         }
-
-        best_solution
     }
 
-    fn generate(&self, max_attempts: usize) -> Option<(Grid, Vec<PlacedWord>)> {
-        if !self.silent {
-            println!("Generating word search puzzle...");
-            println!("Horizontal words: {:?}", self.horizontal_words);
-            println!("Vertical words: {:?}", self.vertical_words);
-            println!();
+    Ok(Outcome::Complete)
+}
+
+// Generate with successive seeds until a layout differs in enough cells from every
+// earlier variant, giving up after VARIANT_SEED_TRIES seeds
+fn generate_variant(args: &Args, generator: &mut WordSearchGenerator, style: &RenderStyle, variants: &mut Variants)
+    -> Option<(Grid, Vec<PlacedWord>)> {
+    let plain_style = RenderStyle { color: false, coords: false, ..style.clone() };
+    let mut too_similar = false;
+    for _ in 0..VARIANT_SEED_TRIES {
+        let seed = variants.next_seed;
+        variants.next_seed = seed.wrapping_add(1);
+        generator.set_config(GenerationConfig { seed: Some(seed), ..generator.config().clone() });
+        let Some((grid, placed_words)) = generator.generate(args.max_attempts) else {
+            continue;
+        };
+        let export = grid.export(&placed_words, &plain_style, None, None, None);
+        let difference = variants.earlier.iter().map(|earlier| export.difference(earlier)).fold(1.0, f64::min);
+        if difference >= args.variant_difference {
+            variants.earlier.push(export.snapshot());
+            return Some((grid, placed_words));
         }
+        too_similar = true;
+        if !args.silent {
+            println!("Seed {} differs from an earlier variant in only {:.0}% of cells, trying the next seed", seed, difference * 100.0);
+        }
+    }
+    variants.exhausted = too_similar;
+    None
+}
 
-        let (initial_width, initial_height) = self.estimate_grid_size();
-        
-        // Try multiple advanced algorithms in order of sophistication
-        let algorithms = [
-            ("optimized", 0.6, max_attempts / 5),        // Start very small
-            ("intersection-first", 0.7, max_attempts / 5),
-            ("optimized", 0.8, max_attempts / 5),
-            ("optimized", 1.0, max_attempts / 5),
-            ("standard", 1.2, max_attempts / 5),  // Final fallback
-        ];
-        
-        for (algo_type, multiplier, attempts) in algorithms {
-            let width = ((initial_width as f64) * multiplier) as usize;
-            let height = ((initial_height as f64) * multiplier) as usize;
-            
-            if !self.silent {
-                println!("Trying {} algorithm with grid size: {}x{} ({} attempts)", algo_type, width, height, attempts);
+// Generate each benchmark input with seeds 0..BENCHMARK_RUNS and the current settings,
+// one run at a time so the timings are not skewed by runs competing for the CPU
+fn run_benchmark(args: &Args, config: &GenerationConfig) -> Vec<BenchmarkResult> {
+    BENCHMARK_INPUTS.iter()
+        .map(|&(input, yaml)| {
+            let word_lists: WordLists = serde_yaml::from_str(yaml).expect("bundled benchmark inputs are valid");
+            let mut total_time = Duration::ZERO;
+            let mut solved = Vec::new();
+            for seed in 0..BENCHMARK_RUNS {
+                let mut generator = WordSearchGenerator::new(word_lists.clone(), true);
+                configure_generator(&mut generator, args, &GenerationConfig { seed: Some(seed), ..config.clone() });
+                let start = Instant::now();
+                let result = generator.generate(args.max_attempts);
+                total_time += start.elapsed();
+                if let Some((grid, placed_words)) = result.filter(|(_, placed_words)| generator.missing_words(placed_words).is_empty()) {
+                    let (height, width) = grid.get_used_dimensions();
+                    let area = (height * width) as f64;
+                    let lower_bound = generator.stats().area_lower_bound.max(1) as f64;
+                    solved.push((area, count_crossings(&placed_words) as f64, area / lower_bound));
+                }
             }
-            
-            let solution = match algo_type {
-                "intersection-first" => self.generate_intersection_first(width, height, attempts),
-                "optimized" => self.generate_optimized(width, height, attempts),
-                "standard" => self.generate_with_size(width, height, attempts),
-                _ => None,
+            let mean = |value: fn(&(f64, f64, f64)) -> f64| {
+                (!solved.is_empty()).then(|| solved.iter().map(value).sum::<f64>() / solved.len() as f64)
             };
-            
-            // Apply post-processing optimization to any successful solution
-            if let Some((mut grid, mut placed_words)) = solution {
-                let original_area = grid.get_used_dimensions().0 * grid.get_used_dimensions().1;
-                
-                // Phase 1: Apply simulated annealing for local optimization
-                if !self.silent {
-                    println!("Applying simulated annealing optimization...");
+            BenchmarkResult {
+                input,
+                words: word_lists.horizontal.len() + word_lists.vertical.len(),
+                runs: BENCHMARK_RUNS,
+                successes: solved.len() as u64,
+                mean_time: total_time / BENCHMARK_RUNS as u32,
+                mean_area: mean(|run| run.0),
+                mean_intersections: mean(|run| run.1),
+                mean_compactness: mean(|run| run.2),
+            }
+        })
+        .collect()
+}
+
+fn print_benchmark(results: &[BenchmarkResult]) {
+    println!("{:<8} {:>5} {:>8} {:>11} {:>9} {:>13} {:>11}", "Input", "Words", "Success", "Mean time", "Mean area", "Intersections", "Compactness");
+    let optional = |value: Option<f64>, precision: usize, unit: &str| {
+        value.map_or("-".to_string(), |value| format!("{:.*}{}", precision, value, unit))
+    };
+    for result in results {
+        println!("{:<8} {:>5} {:>7.0}% {:>11.2?} {:>9} {:>13} {:>11}",
+                 result.input, result.words, 100.0 * result.successes as f64 / result.runs as f64, result.mean_time,
+                 optional(result.mean_area, 1, ""), optional(result.mean_intersections, 1, ""), optional(result.mean_compactness, 2, "x"));
+    }
+}
+
+// Write a rendered puzzle to its output file, and its answer key to the solution file
+// if there is one. Without an output file the document is returned for standard output
+#[allow(clippy::too_many_arguments)]
+fn write_puzzle(args: &Args, generator: &WordSearchGenerator, grid: &Grid, placed_words: &[PlacedWord], style: &RenderStyle,
+                title: Option<&str>, instructions: Option<&str>, output_path: Option<&Path>, solution_path: Option<&Path>)
+    -> Result<Option<Vec<u8>>, Box<dyn std::error::Error>> {
+    let document = render_document(args, generator, grid, placed_words, style, title, instructions)?;
+    let unwritten = match output_path {
+        Some(path) => {
+            fs::write(path, document)?;
+            if !args.silent {
+                println!("Written to {}", path.display());
+            }
+            None
+        }
+        None => Some(document),
+    };
+    if let Some(path) = solution_path {
+        let solution_style = RenderStyle { sections: Sections::Solution, ..style.clone() };
+        fs::write(path, render_document(args, generator, grid, placed_words, &solution_style, title, instructions)?)?;
+        if !args.silent {
+            println!("Answer key written to {}", path.display());
+        }
+    }
+    Ok(unwritten)
+}
+
+// Render the puzzle in the selected format, with the sections chosen by the style
+fn render_document(args: &Args, generator: &WordSearchGenerator, grid: &Grid, placed_words: &[PlacedWord], style: &RenderStyle,
+                   title: Option<&str>, instructions: Option<&str>) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let word_bank = generator.word_bank(args.word_bank);
+    let grouped_word_bank = generator.grouped_word_bank(args.word_bank);
+    let document = match args.format {
+        OutputFormat::Text => match style.sections {
+            Sections::Grid => grid.to_text(placed_words, style).into_bytes(),
+            Sections::Solution => grid.answer_key_text(placed_words, style).into_bytes(),
+            Sections::All => {
+                let mut text = String::new();
+                if let (false, Some(title)) = (args.silent, title) {
+                    text.push_str(&format!("{}\n\n", title));
                 }
-                let (optimized_grid, optimized_words) = self.generate_simulated_annealing((grid, placed_words), 100);
-                grid = optimized_grid;
-                placed_words = optimized_words;
-                
-                // Phase 2: Compact the grid
-                let (row_offset, col_offset) = grid.compact();
-                
-                // Update word positions after compaction
-                for word in placed_words.iter_mut() {
-                    word.start_row = word.start_row.saturating_sub(row_offset);
-                    word.start_col = word.start_col.saturating_sub(col_offset);
+                text.push_str(&grid.to_text(placed_words, style));
+                if !args.silent {
+                    text.push('\n');
+                    for group in &grouped_word_bank {
+                        let label = match (&group.label, grouped_word_bank.len()) {
+                            (Some(label), _) => label.as_str(),
+                            (None, 1) => "Words",
+                            (None, _) => "Other",
+                        };
+                        text.push_str(&format!("{}: {}\n", label, group.words.join(", ")));
+                    }
                 }
-                
-                // Phase 3: Try aggressive compaction
-                while grid.try_remove_empty_rows_cols() {
-                    // Keep removing until no more empty rows/cols can be removed
+                if let (false, Some(instructions)) = (args.silent, instructions) {
+                    text.push_str(&format!("\n{}\n", instructions));
                 }
-                
-                let final_area = grid.get_used_dimensions().0 * grid.get_used_dimensions().1;
-                if !self.silent {
-                    println!("Total optimization: {} -> {} area ({:.1}% reduction)", 
-                             original_area, final_area, 
-                             100.0 * (1.0 - final_area as f64 / original_area as f64));
+                if args.answer_key {
+                    text.push_str(&format!("\nAnswer key:\n{}", grid.answer_key_text(placed_words, style)));
                 }
-                
-                return Some((grid, placed_words));
+                text.into_bytes()
             }
+        },
+        OutputFormat::Csv => grid.to_csv(placed_words, style).into_bytes(),
+        OutputFormat::Html => grid.to_html(placed_words, style, title, instructions, &grouped_word_bank).into_bytes(),
+        OutputFormat::Json => {
+            let json = match style.sections {
+                Sections::Grid => serde_json::to_string_pretty(&grid.export_grid(placed_words, style, title, instructions, &word_bank))?,
+                Sections::Solution => serde_json::to_string_pretty(&grid.export_solution(placed_words, style, title))?,
+                Sections::All => {
+                    let stats = generator.stats();
+                    serde_json::to_string_pretty(&grid.export(placed_words, style, title, instructions, Some(&stats)))?
+                }
+            };
+            format!("{}\n", json).into_bytes()
         }
+        OutputFormat::Pdf => grid.to_pdf(placed_words, style, title, instructions, &word_bank, args.page_size),
+        OutputFormat::Dot => grid.to_dot(placed_words, style, title).into_bytes(),
+    };
+    Ok(document)
+}
 
-        None
+// Output path for puzzle `index` of `count`: the path itself for a single puzzle,
+// otherwise numbered from 1 before the extension (puzzles.html -> puzzles-1.html)
+fn numbered_path(path: &Path, index: usize, count: usize) -> PathBuf {
+    if count == 1 {
+        return path.to_path_buf();
     }
+    let stem = path.file_stem().map(|stem| stem.to_string_lossy().into_owned()).unwrap_or_default();
+    let file_name = match path.extension() {
+        Some(extension) => format!("{}-{}.{}", stem, index + 1, extension.to_string_lossy()),
+        None => format!("{}-{}", stem, index + 1),
+    };
+    path.with_file_name(file_name)
 }
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let args = Args::parse();
+fn print_summary(grid: &Grid, placed_words: &[PlacedWord], stats: &GenerationStats) {
+    let (height, width) = grid.get_used_dimensions();
+    let area = height * width;
+    let filled = grid.cells.iter().flatten().filter(|cell| cell.is_some()).count();
+    let hidden_copies = placed_words.iter().filter(|word| word.mirrored).count();
+
+    println!("\nSummary:");
+    if hidden_copies > 0 {
+        println!("  Words placed:       {} (+{} hidden reverse copies)", placed_words.len() - hidden_copies, hidden_copies);
+    } else {
+        println!("  Words placed:       {}", placed_words.len());
+    }
+    println!("  Grid size:          {}x{}", height, width);
+    println!("  Area:               {}", area);
+    println!("  Intersections:      {}", count_crossings(placed_words));
+    println!("  Density:            {:.1}% of cells filled", 100.0 * filled as f64 / area as f64);
+    if stats.area_lower_bound > 0 {
+        println!("  Compactness:        {:.2}x the lower bound of {}", area as f64 / stats.area_lower_bound as f64, stats.area_lower_bound);
+    }
+    if let Some(quality) = stats.quality {
+        println!("  Quality:            {:.0}/100 (compactness {:.2}, connectivity {:.2}, balance {:.2})",
+                 quality.score, quality.compactness, quality.connectivity, quality.balance);
+    }
+    println!("  Winning algorithm:  {}", stats.winning_algorithm.map_or("unknown", |algorithm| algorithm.name()));
+    if let Some(seed) = stats.seed {
+        println!("  Seed:               {}", seed);
+    }
+}
 
-    // Read and parse the input file
-    let input_content = fs::read_to_string(&args.input)?;
-    let word_lists: WordLists = serde_yaml::from_str(&input_content)?;
+// Show the new grid with every changed cell marked, then the words that moved,
+// appeared or disappeared since the earlier puzzle
+fn print_diff(path: &Path, export: &PuzzleExport, old: &PuzzleSnapshot) {
+    let diff = export.diff(old);
+    let (old_size, new_size) = ((old.height, old.width), (export.height, export.width));
+    println!("\nDifferences from {}:", path.display());
+    println!("  Grid size:          {}x{} -> {}x{}", old.height, old.width, export.height, export.width);
+    println!("  Changed cells:      {} (marked *)", diff.changed_cells.len());
+
+    let height = export.height.max(old.height);
+    let width = export.width.max(old.width);
+    for r in 0..height {
+        let row: Vec<String> = (0..width)
+            .map(|c| {
+                let text = export.grid.get(r).and_then(|row| row.get(c)).cloned().flatten().unwrap_or_else(|| ".".to_string());
+                let marker = if diff.changed_cells.contains(&(r, c)) { '*' } else { ' ' };
+                format!("{}{}", text, marker)
+            })
+            .collect();
+        println!("  {}", row.join(" ").trim_end());
+    }
 
-    // Validate input
-    if word_lists.horizontal.is_empty() && word_lists.vertical.is_empty() {
-        eprintln!("Error: No words provided in input file");
-        std::process::exit(1);
+    // First letters count from the new puzzle's origin, over the grid each word came from
+    let position = |word: &PlacedWord, (height, width): (usize, usize)| {
+        let first = word.cells().next().unwrap_or((word.start_row, word.start_col));
+        let (row, col) = export.origin.locate(first, (0, height.saturating_sub(1), 0, width.saturating_sub(1)));
+        format!("({}, {}) {:?}{}", row, col, word.direction, if word.reversed { ", reversed" } else { "" })
+    };
+    for (old_word, new_word) in &diff.moved_words {
+        println!("  Moved:   {} {} -> {}", new_word.display_word(), position(old_word, old_size), position(new_word, new_size));
+    }
+    for word in &diff.added_words {
+        println!("  Added:   {} {}", word.display_word(), position(word, new_size));
+    }
+    for word in &diff.removed_words {
+        println!("  Removed: {} {}", word.display_word(), position(word, old_size));
     }
+}
 
-    // Create generator and generate puzzle
-    let generator = WordSearchGenerator::new(word_lists, args.silent);
-    
-    match generator.generate(args.max_attempts) {
-        Some((grid, placed_words)) => {
-            if !args.silent {
-                println!("\nSuccessfully generated word search!");
-                let (height, width) = grid.get_used_dimensions();
-                println!("Final grid size: {}x{} (area: {})", height, width, height * width);
-                println!("\nPlaced words:");
-                for word in &placed_words {
-                    println!("  {} ({:?}) at ({}, {})", 
-                             word.word, word.direction, word.start_row, word.start_col);
-                }
-                println!("\nGrid:");
-            }
-            grid.print();
-        }
-        None => {
-            eprintln!("Failed to generate word search puzzle. Try increasing --max-attempts or using shorter words.");
-            std::process::exit(1);
-        }
+fn print_timings(stats: &GenerationStats) {
+    println!("\nTiming:");
+    for stage in &stats.stages {
+        println!("  {:<20} {:>3}x{:<3} {:>9} attempts  {:>10.2?}  {}",
+                 stage.algorithm.name(), stage.width, stage.height, format!("{}/{}", stage.attempts_used, stage.attempts), stage.duration,
+                 if stage.succeeded { "succeeded" } else { "failed" });
     }
+    println!("  {:<20} {:>38.2?}", "annealing", stats.annealing_time);
+    println!("  {:<20} {:>38.2?}", "compaction", stats.compaction_time);
+}
 
+// Redraw the placed words one at a time on an empty grid of the same size, in placement order
+fn print_placement_trace(grid: &Grid, placed_words: &[PlacedWord], style: &RenderStyle) {
+    for (step, (frame, word)) in grid.placement_frames(placed_words).iter().zip(placed_words).enumerate() {
+        println!("\nStep {}/{}: {} ({:?}{})", step + 1, placed_words.len(), word.display_word(), word.direction,
+                 if word.reversed { ", reversed" } else { "" });
+        frame.print(&placed_words[..=step], style);
+    }
+}
+
+// Write one SVG image per placement step, with the word added in that step shaded
+fn write_placement_frames(dir: &Path, grid: &Grid, placed_words: &[PlacedWord], style: &RenderStyle) -> std::io::Result<()> {
+    fs::create_dir_all(dir)?;
+    let digits = placed_words.len().to_string().len();
+    for (step, (frame, word)) in grid.placement_frames(placed_words).iter().zip(placed_words).enumerate() {
+        let path = dir.join(format!("frame-{:0width$}.svg", step + 1, width = digits));
+        fs::write(path, frame.to_svg(&placed_words[..=step], style, Some(word)))?;
+    }
     Ok(())
-}
\ No newline at end of file
+}
+
+fn print_analysis(analysis: &WordListAnalysis) {
+    println!("Letter  Count  Horizontal  Vertical");
+    for frequency in &analysis.letters {
+        println!("{:<6}  {:>5}  {:>10}  {:>8}{}", frequency.letter, frequency.count,
+                 frequency.horizontal, frequency.vertical,
+                 if frequency.rare { "  (rare)" } else { "" });
+    }
+    println!();
+    println!("Total letters: {}", analysis.total_letters);
+    println!("Possible intersections: {}", analysis.possible_intersections);
+
+    let rare: Vec<String> = analysis.letters.iter()
+        .filter(|frequency| frequency.rare)
+        .map(|frequency| frequency.letter.to_string())
+        .collect();
+    if !rare.is_empty() {
+        println!("Rare letters (hard to intersect on): {}", rare.join(" "));
+    }
+    if !analysis.disconnected_words.is_empty() {
+        println!("Words that cannot cross any word in the other direction: {:?}", analysis.disconnected_words);
+    }
+}
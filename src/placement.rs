@@ -0,0 +1,43 @@
+// Placement geometry shared by the library and the dependency-free simple.rs build, so
+// both agree on where a word goes. It uses only std, and simple.rs pulls it in with
+// `#[path]`. A word is anchored at the cell of its last letter and its letters run
+// right (horizontal) or down (vertical) to that cell
+
+// Cells, first letter first, of a word of `len` letters with its last letter at
+// (row, col). None if the word is empty or would run off the top or left edge
+pub fn word_cells(len: usize, row: usize, col: usize, horizontal: bool) -> Option<impl Iterator<Item = (usize, usize)>> {
+    let anchor = if horizontal { col } else { row };
+    if len == 0 || anchor + 1 < len {
+        return None;
+    }
+    Some((0..len).map(move |i| if horizontal {
+        (row, col + 1 - len + i)
+    } else {
+        (row + 1 - len + i, col)
+    }))
+}
+
+// Whether `word` fits with its last letter at (row, col): inside the grid, on cells
+// `open` accepts, and agreeing with every letter already in `cells`
+pub fn fits(cells: &[Vec<Option<char>>], word: &str, row: usize, col: usize, horizontal: bool,
+            mut open: impl FnMut(usize, usize) -> bool) -> bool {
+    let width = cells.first().map_or(0, |first| first.len());
+    if row >= cells.len() || col >= width {
+        return false;
+    }
+    let Some(positions) = word_cells(word.chars().count(), row, col, horizontal) else {
+        return false;
+    };
+    positions.zip(word.chars()).all(|((r, c), ch)| {
+        open(r, c) && cells[r][c].is_none_or(|existing| existing == ch)
+    })
+}
+
+// Write the letters of `word` with its last letter at (row, col). The placement must
+// already have been checked with `fits`
+pub fn write(cells: &mut [Vec<Option<char>>], word: &str, row: usize, col: usize, horizontal: bool) {
+    let positions = word_cells(word.chars().count(), row, col, horizontal).expect("placement was checked with fits");
+    for ((r, c), ch) in positions.zip(word.chars()) {
+        cells[r][c] = Some(ch);
+    }
+}
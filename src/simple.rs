@@ -1,11 +1,19 @@
 // Simple word search generator without external dependencies
 // This version can be compiled with just: rustc src/simple.rs
+// It shares the placement geometry of the full build through src/placement.rs
 
 use std::env;
 use std::fs;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[path = "placement.rs"]
+mod placement;
 
 struct Grid {
     cells: Vec<Vec<Option<char>>>,
+    // Whether a horizontal / vertical word already covers each cell, so a word only
+    // shares cells with words running the other way
+    covered: Vec<Vec<(bool, bool)>>,
     width: usize,
     height: usize,
 }
@@ -14,55 +22,28 @@ impl Grid {
     fn new(width: usize, height: usize) -> Self {
         Grid {
             cells: vec![vec![None; width]; height],
+            covered: vec![vec![(false, false); width]; height],
             width,
             height,
         }
     }
 
+    // (row, col) is the cell of the word's last letter
     fn can_place_horizontal(&self, word: &str, row: usize, col: usize) -> bool {
-        let chars: Vec<char> = word.chars().collect();
-        if col + 1 < chars.len() {
-            return false;
-        }
-        let start_col = col + 1 - chars.len();
-        
-        for (i, &ch) in chars.iter().enumerate() {
-            let c = start_col + i;
-            if let Some(existing) = self.cells[row][c] {
-                if existing != ch {
-                    return false;
-                }
-            }
-        }
-        true
+        placement::fits(&self.cells, word, row, col, true, |r, c| !self.covered[r][c].0)
     }
 
     fn can_place_vertical(&self, word: &str, row: usize, col: usize) -> bool {
-        let chars: Vec<char> = word.chars().collect();
-        if row + 1 < chars.len() {
-            return false;
-        }
-        let start_row = row + 1 - chars.len();
-        
-        for (i, &ch) in chars.iter().enumerate() {
-            let r = start_row + i;
-            if let Some(existing) = self.cells[r][col] {
-                if existing != ch {
-                    return false;
-                }
-            }
-        }
-        true
+        placement::fits(&self.cells, word, row, col, false, |r, c| !self.covered[r][c].1)
     }
 
     fn place_horizontal(&mut self, word: &str, row: usize, col: usize) -> bool {
         if !self.can_place_horizontal(word, row, col) {
             return false;
         }
-        let chars: Vec<char> = word.chars().collect();
-        let start_col = col + 1 - chars.len();
-        for (i, &ch) in chars.iter().enumerate() {
-            self.cells[row][start_col + i] = Some(ch);
+        placement::write(&mut self.cells, word, row, col, true);
+        for (r, c) in placement::word_cells(word.chars().count(), row, col, true).into_iter().flatten() {
+            self.covered[r][c].0 = true;
         }
         true
     }
@@ -71,14 +52,53 @@ impl Grid {
         if !self.can_place_vertical(word, row, col) {
             return false;
         }
-        let chars: Vec<char> = word.chars().collect();
-        let start_row = row + 1 - chars.len();
-        for (i, &ch) in chars.iter().enumerate() {
-            self.cells[start_row + i][col] = Some(ch);
+        placement::write(&mut self.cells, word, row, col, false);
+        for (r, c) in placement::word_cells(word.chars().count(), row, col, false).into_iter().flatten() {
+            self.covered[r][c].1 = true;
         }
         true
     }
 
+    // Last-letter cells at which the word would cross at least one placed letter
+    fn crossing_positions(&self, word: &str, horizontal: bool) -> Vec<(usize, usize)> {
+        let chars: Vec<char> = word.chars().collect();
+        let mut positions = Vec::new();
+        for row in 0..self.height {
+            for col in 0..self.width {
+                let Some(letter) = self.cells[row][col] else {
+                    continue;
+                };
+                for (i, &ch) in chars.iter().enumerate() {
+                    if ch != letter {
+                        continue;
+                    }
+                    let (end_row, end_col) = if horizontal {
+                        (row, col + chars.len() - 1 - i)
+                    } else {
+                        (row + chars.len() - 1 - i, col)
+                    };
+                    let fits = if horizontal {
+                        self.can_place_horizontal(word, end_row, end_col)
+                    } else {
+                        self.can_place_vertical(word, end_row, end_col)
+                    };
+                    if fits {
+                        positions.push((end_row, end_col));
+                    }
+                }
+            }
+        }
+        positions
+    }
+
+    fn place(&mut self, word: &str, row: usize, col: usize, horizontal: bool) -> bool {
+        if horizontal {
+            self.place_horizontal(word, row, col)
+        } else {
+            self.place_vertical(word, row, col)
+        }
+    }
+
     fn print(&self) {
         for row in &self.cells {
             for cell in row {
@@ -92,6 +112,53 @@ impl Grid {
     }
 }
 
+// Linear congruential generator, so the randomness needs no crates
+struct Lcg(u64);
+
+impl Lcg {
+    fn from_time() -> Self {
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_nanos() as u64).unwrap_or(0);
+        Lcg(nanos)
+    }
+
+    // Random number in 0..n (n > 0)
+    fn below(&mut self, n: usize) -> usize {
+        self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        ((self.0 >> 33) % n as u64) as usize
+    }
+}
+
+const ATTEMPTS_PER_SIZE: usize = 20;
+const RANDOM_TRIES: usize = 200;
+
+// Place every word, longest first: at a random position crossing a placed letter if
+// there is one, otherwise at a random free position. Returns None if some word does
+// not fit
+fn try_place_all(words: &[(String, bool)], size: usize, rng: &mut Lcg) -> Option<Grid> {
+    let mut grid = Grid::new(size, size);
+    for (word, horizontal) in words {
+        let len = word.chars().count();
+        let crossings = grid.crossing_positions(word, *horizontal);
+        if !crossings.is_empty() {
+            let (row, col) = crossings[rng.below(crossings.len())];
+            grid.place(word, row, col, *horizontal);
+            continue;
+        }
+        let placed = (0..RANDOM_TRIES).any(|_| {
+            let (row, col) = if *horizontal {
+                (rng.below(size), len - 1 + rng.below(size + 1 - len))
+            } else {
+                (len - 1 + rng.below(size + 1 - len), rng.below(size))
+            };
+            grid.place(word, row, col, *horizontal)
+        });
+        if !placed {
+            return None;
+        }
+    }
+    Some(grid)
+}
+
 fn parse_simple_input(content: &str) -> (Vec<String>, Vec<String>) {
     let lines: Vec<&str> = content.lines().collect();
     let mut horizontal = Vec::new();
@@ -145,24 +212,29 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("Horizontal words: {:?}", horizontal_words);
     println!("Vertical words: {:?}", vertical_words);
 
-    // Simple grid creation
-    let mut grid = Grid::new(20, 20);
-    
-    // Place some words manually for demonstration
-    if let Some(word) = horizontal_words.get(0) {
-        grid.place_horizontal(word, 5, 15);
-        println!("Placed '{}' horizontally", word);
-    }
-    
-    if let Some(word) = vertical_words.get(0) {
-        grid.place_vertical(word, 15, 8);
-        println!("Placed '{}' vertically", word);
-    }
-
-    if let Some(word) = horizontal_words.get(1) {
-        grid.place_horizontal(word, 10, 12);
-        println!("Placed '{}' horizontally", word);
+    let mut words: Vec<(String, bool)> = horizontal_words.into_iter().map(|word| (word, true))
+        .chain(vertical_words.into_iter().map(|word| (word, false)))
+        .filter(|(word, _)| !word.is_empty())
+        .collect();
+    if words.is_empty() {
+        eprintln!("No words found in {}", args[1]);
+        std::process::exit(1);
     }
+    words.sort_by_key(|(word, _)| std::cmp::Reverse(word.chars().count()));
+
+    // Start from a square holding every letter once and grow it whenever a size
+    // keeps failing
+    let letters: usize = words.iter().map(|(word, _)| word.chars().count()).sum();
+    let longest = words[0].0.chars().count();
+    let mut size = longest.max((letters as f64).sqrt().ceil() as usize);
+    let mut rng = Lcg::from_time();
+    let grid = loop {
+        if let Some(grid) = (0..ATTEMPTS_PER_SIZE).find_map(|_| try_place_all(&words, size, &mut rng)) {
+            break grid;
+        }
+        size += 1;
+    };
+    println!("Placed all {} words in a {}x{} grid", words.len(), size, size);
 
     println!("\nGenerated grid:");
     grid.print();
@@ -0,0 +1,16 @@
+// Helpers shared by the integration tests that build and configure a generator
+
+use wordsearch_generator::{GenerationConfig, WordLists, WordSearchGenerator};
+
+// Word lists holding the given horizontal and vertical words
+pub fn word_lists(horizontal: &[&str], vertical: &[&str]) -> WordLists {
+    WordLists::new(horizontal.iter().map(|word| word.to_string()).collect(),
+                   vertical.iter().map(|word| word.to_string()).collect())
+}
+
+// Silent generator for the word lists with the given configuration
+pub fn generator(words: WordLists, config: GenerationConfig) -> WordSearchGenerator {
+    let mut generator = WordSearchGenerator::new(words, true);
+    generator.set_config(config);
+    generator
+}
@@ -0,0 +1,107 @@
+// Words exactly as long as the grid is wide or tall leave a single anchor column or row:
+// the standard algorithm draws it from the one-value range len-1..extent and the others
+// take it from the candidate positions. Whichever algorithm runs, the word must still be
+// placed, at the very edge of the grid, and a word one letter longer must not be placed at all
+
+mod common;
+
+use clap::ValueEnum;
+use common::{generator, word_lists};
+use wordsearch_generator::{Algorithm, Direction, GenerationConfig};
+
+// Generate with one algorithm at a fixed size and return where each word went
+fn placements(horizontal: &[&str], vertical: &[&str], width: usize, height: usize, algorithm: Algorithm)
+    -> Vec<(String, Direction, usize, usize)> {
+    let generator = generator(word_lists(horizontal, vertical), GenerationConfig {
+        fixed_width: Some(width),
+        fixed_height: Some(height),
+        algorithm: Some(algorithm),
+        seed: Some(3),
+        skip_compaction: true,
+        ..GenerationConfig::default()
+    });
+    let (grid, placed_words) = generator.generate(50)
+        .unwrap_or_else(|| panic!("{:?} found no layout for an exactly fitting word", algorithm));
+    assert_eq!((grid.width, grid.height), (width, height), "{:?} grew the grid", algorithm);
+    assert!(grid.misplaced_words(&placed_words).is_empty(), "{:?} misplaced a word", algorithm);
+    placed_words.into_iter().map(|word| (word.word, word.direction, word.start_row, word.start_col)).collect()
+}
+
+#[test]
+fn horizontal_word_spanning_the_width() {
+    for &algorithm in Algorithm::value_variants() {
+        let placed = placements(&["PLANET"], &["NET"], 6, 4, algorithm);
+        let planet = placed.iter().find(|(word, ..)| word == "PLANET").expect("PLANET is placed");
+        assert_eq!((planet.1, planet.3), (Direction::Horizontal, 0), "{:?}", algorithm);
+    }
+}
+
+#[test]
+fn vertical_word_spanning_the_height() {
+    for &algorithm in Algorithm::value_variants() {
+        let placed = placements(&["NET"], &["PLANET"], 4, 6, algorithm);
+        let planet = placed.iter().find(|(word, ..)| word == "PLANET").expect("PLANET is placed");
+        assert_eq!((planet.1, planet.2), (Direction::Vertical, 0), "{:?}", algorithm);
+    }
+}
+
+#[test]
+fn words_spanning_both_sides_of_a_square_grid() {
+    for &algorithm in Algorithm::value_variants() {
+        let placed = placements(&["STONE"], &["SPORT"], 5, 5, algorithm);
+        assert_eq!(placed.len(), 2, "{:?}", algorithm);
+        for (word, direction, row, col) in placed {
+            match direction {
+                Direction::Horizontal => assert_eq!(col, 0, "{:?} placed {}", algorithm, word),
+                Direction::Vertical => assert_eq!(row, 0, "{:?} placed {}", algorithm, word),
+            }
+        }
+    }
+}
+
+#[test]
+fn word_longer_than_the_grid_is_not_placed() {
+    for &algorithm in Algorithm::value_variants() {
+        let generator = generator(word_lists(&["PLANETS"], &["NET"]), GenerationConfig {
+            fixed_width: Some(6),
+            fixed_height: Some(4),
+            algorithm: Some(algorithm),
+            seed: Some(3),
+            ..GenerationConfig::default()
+        });
+        assert!(generator.generate(20).is_none(), "{:?} placed a word longer than the grid", algorithm);
+    }
+}
+
+#[test]
+fn forced_crossing_at_first_or_last_letters_in_each_corner() {
+    // Each pair shares one letter, at an end of both words, so the only anchor the
+    // intersection-first algorithm can force is the corner where those ends meet.
+    // Given as (H start, V start), each the (row, col) of the word's first cell
+    let cases = [
+        ("CAT", "COD", (0, 0), (0, 0)),
+        ("CAT", "TOP", (0, 0), (0, 2)),
+        ("TOP", "CAT", (2, 0), (0, 0)),
+        ("CAT", "PIT", (2, 0), (0, 2)),
+    ];
+    for (horizontal, vertical, h_start, v_start) in cases {
+        let placed = placements(&[horizontal], &[vertical], 3, 3, Algorithm::IntersectionFirst);
+        let start = |word: &str| placed.iter().find(|(placed, ..)| placed == word).map(|&(_, _, row, col)| (row, col));
+        assert_eq!(start(horizontal), Some(h_start), "{} across {}", horizontal, vertical);
+        assert_eq!(start(vertical), Some(v_start), "{} across {}", vertical, horizontal);
+    }
+}
+
+#[test]
+fn grid_with_no_columns_or_rows_is_not_filled() {
+    // A list in one direction only never needs the other extent, but a grid with no
+    // columns or no rows still has nowhere to put a letter
+    for (horizontal, vertical, width, height) in [(vec![], vec!["CAT"], 0, 5), (vec!["CAT"], vec![], 5, 0)] {
+        let config = GenerationConfig { fixed_width: Some(width), fixed_height: Some(height), seed: Some(3), ..GenerationConfig::default() };
+        assert!(config.validate().is_err());
+        for &algorithm in Algorithm::value_variants() {
+            let generator = generator(word_lists(&horizontal, &vertical), GenerationConfig { algorithm: Some(algorithm), ..config.clone() });
+            assert!(generator.generate(20).is_none(), "{:?} filled a {}x{} grid", algorithm, width, height);
+        }
+    }
+}
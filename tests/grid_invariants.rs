@@ -0,0 +1,124 @@
+// Property tests for the grid operations the generator chains together: random
+// sequences of placements, removals and compactions must keep every placed word
+// readable from its recorded position and never lose or reorder a letter
+
+use proptest::prelude::*;
+use wordsearch_generator::{Direction, Grid, PlacedWord};
+
+const GRID_SIZE: usize = 10;
+
+#[derive(Debug, Clone)]
+enum Op {
+    // Word with its last letter at (row, col), as Grid::place_word takes it
+    Place { word: String, row: usize, col: usize, direction: Direction },
+    // Index into the placed words, taken modulo their number
+    Remove(usize),
+    Compact,
+    RemoveEmptyRowsCols,
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    // A small alphabet so that words often cross and overlap
+    let place = ("[ABC]{1,5}", 0..GRID_SIZE, 0..GRID_SIZE, prop_oneof![Just(Direction::Horizontal), Just(Direction::Vertical)])
+        .prop_map(|(word, row, col, direction)| Op::Place { word, row, col, direction });
+    prop_oneof![
+        4 => place,
+        2 => any::<usize>().prop_map(Op::Remove),
+        1 => Just(Op::Compact),
+        1 => Just(Op::RemoveEmptyRowsCols),
+    ]
+}
+
+// Letters of the grid in reading order
+fn letters(grid: &Grid) -> Vec<char> {
+    grid.cells.iter().flatten().flatten().copied().collect()
+}
+
+fn check_invariants(grid: &Grid, placed_words: &[PlacedWord]) -> Result<(), TestCaseError> {
+    prop_assert_eq!(grid.cells.len(), grid.height);
+    prop_assert!(grid.cells.iter().all(|row| row.len() == grid.width));
+    prop_assert!(grid.misplaced_words(placed_words).is_empty(), "a word no longer reads from its position");
+
+    // Every letter belongs to a placed word
+    for (r, row) in grid.cells.iter().enumerate() {
+        for (c, cell) in row.iter().enumerate() {
+            if cell.is_some() {
+                prop_assert!(placed_words.iter().any(|word| word.cells().any(|cell| cell == (r, c))),
+                             "letter at ({}, {}) belongs to no word", r, c);
+            }
+        }
+    }
+
+    // The bounds hold every letter and each of their edges touches one
+    match grid.bounding_box() {
+        None => {
+            prop_assert!(letters(grid).is_empty());
+            prop_assert_eq!(grid.get_used_dimensions(), (0, 0));
+        }
+        Some((min_row, max_row, min_col, max_col)) => {
+            prop_assert!(min_row <= max_row && max_row < grid.height);
+            prop_assert!(min_col <= max_col && max_col < grid.width);
+            let filled = |r: usize, c: usize| grid.cells[r][c].is_some();
+            prop_assert!((min_col..=max_col).any(|c| filled(min_row, c)));
+            prop_assert!((min_col..=max_col).any(|c| filled(max_row, c)));
+            prop_assert!((min_row..=max_row).any(|r| filled(r, min_col)));
+            prop_assert!((min_row..=max_row).any(|r| filled(r, max_col)));
+            let inside = (min_row..=max_row).flat_map(|r| (min_col..=max_col).map(move |c| (r, c)))
+                .filter(|&(r, c)| filled(r, c))
+                .count();
+            prop_assert_eq!(inside, letters(grid).len());
+            prop_assert_eq!(grid.get_used_dimensions(), (max_row - min_row + 1, max_col - min_col + 1));
+        }
+    }
+    Ok(())
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(500))]
+
+    #[test]
+    fn grid_operations_keep_invariants(ops in prop::collection::vec(op(), 1..40)) {
+        let mut grid = Grid::new(GRID_SIZE, GRID_SIZE);
+        let mut placed_words: Vec<PlacedWord> = Vec::new();
+
+        for op in ops {
+            match op {
+                Op::Place { word, row, col, direction } => {
+                    let fits = grid.can_place_word(&word, row, col, direction);
+                    prop_assert_eq!(grid.place_word(&word, row, col, direction), fits);
+                    if fits {
+                        placed_words.push(PlacedWord::ending_at(word, row, col, direction));
+                    }
+                }
+                Op::Remove(index) => {
+                    if placed_words.is_empty() {
+                        continue;
+                    }
+                    let word = placed_words.remove(index % placed_words.len());
+                    grid.remove_word(&word, &placed_words);
+                }
+                Op::Compact => {
+                    let before = letters(&grid);
+                    let (row_offset, col_offset) = grid.compact();
+                    for word in placed_words.iter_mut() {
+                        // The offset is the top-left of the bounds, so no word lies before it
+                        prop_assert!(word.start_row >= row_offset && word.start_col >= col_offset);
+                        word.start_row -= row_offset;
+                        word.start_col -= col_offset;
+                    }
+                    prop_assert_eq!(letters(&grid), before);
+                    if let Some(bounds) = grid.bounding_box() {
+                        prop_assert_eq!(bounds, (0, grid.height - 1, 0, grid.width - 1));
+                    }
+                }
+                Op::RemoveEmptyRowsCols => {
+                    let before = letters(&grid);
+                    grid.try_remove_empty_rows_cols(&mut placed_words);
+                    prop_assert_eq!(letters(&grid), before);
+                    prop_assert!(grid.cells.iter().all(|row| row.iter().any(Option::is_some)));
+                }
+            }
+            check_invariants(&grid, &placed_words)?;
+        }
+    }
+}
@@ -0,0 +1,20 @@
+// Words are placed without their spaces and hyphens, so "WELL-BEING" fills nine cells,
+// while the word bank and answer key keep the form given
+
+mod common;
+
+use common::{generator, word_lists};
+use wordsearch_generator::{count_crossings, GenerationConfig, WordBankOrder};
+
+#[test]
+fn hyphen_is_kept_in_the_word_bank_but_not_the_grid() {
+    let generator = generator(word_lists(&["WELL-BEING"], &["GLOW"]), GenerationConfig::default());
+    let (grid, placed_words) = generator.generate(50).expect("words should fit");
+
+    let well_being = placed_words.iter().find(|word| word.word == "WELLBEING").expect("WELL-BEING is placed");
+    assert_eq!(well_being.display_word(), "WELL-BEING");
+    assert_eq!(well_being.cells().count(), 9);
+    assert_eq!(count_crossings(&placed_words), 1);
+    assert!(grid.cells.iter().flatten().all(|&cell| cell != Some('-')));
+    assert_eq!(generator.word_bank(WordBankOrder::Alpha), ["GLOW", "WELL-BEING"]);
+}
@@ -0,0 +1,61 @@
+// Annealing keeps the total crossing count up to date as single words move, adding
+// twice the change in the moved word's own crossings rather than recounting. Over
+// random layouts and random moves that running total must match a full recount
+
+use proptest::prelude::*;
+use wordsearch_generator::{word_crossings, Direction, Grid, PlacedWord};
+
+const GRID_SIZE: usize = 8;
+
+fn direction() -> impl Strategy<Value = Direction> {
+    prop_oneof![Just(Direction::Horizontal), Just(Direction::Vertical)]
+}
+
+// A word with its last letter at (row, col), as Grid::place_word takes it
+fn placement() -> impl Strategy<Value = (String, usize, usize, Direction)> {
+    ("[ABC]{2,4}", 0..GRID_SIZE, 0..GRID_SIZE, direction())
+}
+
+fn place(grid: &mut Grid, word: &str, row: usize, col: usize, direction: Direction) -> Option<PlacedWord> {
+    if !grid.place_word(word, row, col, direction) {
+        return None;
+    }
+    Some(PlacedWord::ending_at(word, row, col, direction))
+}
+
+fn recount(placed_words: &[PlacedWord]) -> usize {
+    placed_words.iter().map(|word| word_crossings(word, placed_words)).sum()
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(300))]
+
+    #[test]
+    fn incremental_count_matches_recount(layout in prop::collection::vec(placement(), 1..12),
+                                         moves in prop::collection::vec((any::<usize>(), 0..GRID_SIZE, 0..GRID_SIZE, direction()), 1..40)) {
+        let mut grid = Grid::new(GRID_SIZE, GRID_SIZE);
+        let mut placed_words: Vec<PlacedWord> = layout.iter()
+            .filter_map(|(word, row, col, direction)| place(&mut grid, word, *row, *col, *direction))
+            .collect();
+        prop_assume!(!placed_words.is_empty());
+        let mut total = recount(&placed_words);
+
+        for (index, row, col, direction) in moves {
+            // Take a word out and put it somewhere else, or back where it was if it does not fit
+            let index = index % placed_words.len();
+            let old_crossings = word_crossings(&placed_words[index], &placed_words);
+            let word = placed_words.remove(index);
+            grid.remove_word(&word, &placed_words);
+            let moved = place(&mut grid, &word.word, row, col, direction).unwrap_or_else(|| {
+                let (end_row, end_col) = word.end_position();
+                place(&mut grid, &word.word, end_row, end_col, word.direction).expect("the word fits where it was")
+            });
+            placed_words.push(moved);
+            let new_crossings = word_crossings(&placed_words[placed_words.len() - 1], &placed_words);
+            total = total.checked_add_signed(2 * (new_crossings as isize - old_crossings as isize)).expect("the total stays positive");
+
+            prop_assert_eq!(total, recount(&placed_words));
+            prop_assert!(grid.misplaced_words(&placed_words).is_empty());
+        }
+    }
+}
@@ -0,0 +1,63 @@
+// max_intersections_per_word caps every word's crossings in an accepted solution,
+// whichever algorithm found it; a cap of 0 means no word crosses another at all
+
+mod common;
+
+use clap::ValueEnum;
+use common::{generator, word_lists};
+use wordsearch_generator::{count_crossings, Algorithm, GenerationConfig, PlacedWord};
+
+fn generate(cap: usize, algorithm: Option<Algorithm>, seed: u64) -> Option<Vec<PlacedWord>> {
+    let words = word_lists(&["THREE", "SEVEN", "TEN", "NINE"], &["EIGHT", "ELEVEN", "ONE", "TWENTY"]);
+    let generator = generator(words, GenerationConfig {
+        max_intersections_per_word: Some(cap),
+        algorithm,
+        seed: Some(seed),
+        ..GenerationConfig::default()
+    });
+    generator.generate(50).map(|(_, placed_words)| placed_words)
+}
+
+// Cells of the word that a word of the other direction passes through. Two words of
+// that direction overlapping on one cell still make a single crossing
+fn crossings(word: &PlacedWord, placed_words: &[PlacedWord]) -> usize {
+    word.cells()
+        .filter(|cell| placed_words.iter().any(|other| other.direction != word.direction && other.cells().any(|other_cell| other_cell == *cell)))
+        .count()
+}
+
+// The default staged run and each algorithm on its own, which may find no layout at all
+fn algorithms() -> impl Iterator<Item = Option<Algorithm>> {
+    std::iter::once(None).chain(Algorithm::value_variants().iter().copied().map(Some))
+}
+
+#[test]
+fn every_word_stays_within_the_cap() {
+    for cap in [1, 2] {
+        let mut accepted = 0;
+        for algorithm in algorithms() {
+            for seed in 0..4 {
+                let Some(placed_words) = generate(cap, algorithm, seed) else { continue };
+                accepted += 1;
+                for word in &placed_words {
+                    assert!(crossings(word, &placed_words) <= cap, "{:?} seed {}: {} has more than {} crossings",
+                            algorithm, seed, word.word, cap);
+                }
+            }
+        }
+        assert!(accepted > 0, "no layout found with at most {} crossings per word", cap);
+    }
+}
+
+#[test]
+fn cap_of_zero_means_no_crossings() {
+    let mut accepted = 0;
+    for algorithm in algorithms() {
+        for seed in 0..4 {
+            let Some(placed_words) = generate(0, algorithm, seed) else { continue };
+            accepted += 1;
+            assert_eq!(count_crossings(&placed_words), 0, "{:?} seed {}", algorithm, seed);
+        }
+    }
+    assert!(accepted > 0, "no layout found without crossings");
+}
@@ -0,0 +1,52 @@
+// JSON output counts its grid rows and word cells from the chosen origin, while each
+// word's start stays in the grid's own indices. For every origin, the exported cells
+// must spell the words in the exported grid, and reading the output back must compare
+// equal to the puzzle it came from, whichever origin the earlier puzzle used
+
+mod common;
+
+use clap::ValueEnum;
+use common::{generator, word_lists};
+use wordsearch_generator::{GenerationConfig, Grid, Origin, PlacedWord, PuzzleSnapshot, RenderStyle};
+
+fn puzzle() -> (Grid, Vec<PlacedWord>) {
+    let generator = generator(word_lists(&["CAT", "DOG"], &["TOAD"]), GenerationConfig { seed: Some(1), ..GenerationConfig::default() });
+    generator.generate(100).expect("the words fit")
+}
+
+fn style(origin: Origin) -> RenderStyle {
+    RenderStyle { origin, ..RenderStyle::default() }
+}
+
+#[test]
+fn exported_cells_spell_the_words_from_every_origin() {
+    let (grid, placed_words) = puzzle();
+    for &origin in Origin::value_variants() {
+        let json = serde_json::to_value(grid.export(&placed_words, &style(origin), None, None, None)).unwrap();
+        let rows = json["grid"].as_array().unwrap();
+        for word in json["words"].as_array().unwrap() {
+            let spelled: String = word["cells"].as_array().unwrap().iter()
+                .map(|cell| rows[cell[0].as_u64().unwrap() as usize][cell[1].as_u64().unwrap() as usize].as_str().unwrap())
+                .collect();
+            assert_eq!(spelled, word["word"].as_str().unwrap(), "{:?}", origin);
+        }
+    }
+}
+
+#[test]
+fn json_round_trip_compares_equal_from_every_origin() {
+    let (grid, placed_words) = puzzle();
+    for &origin in Origin::value_variants() {
+        let json = serde_json::to_string(&grid.export(&placed_words, &style(origin), None, None, None)).unwrap();
+        let snapshot: PuzzleSnapshot = serde_json::from_str(&json).unwrap();
+        assert_eq!(snapshot.origin, origin);
+        assert!(grid.misplaced_words(&snapshot.words).is_empty(), "{:?} starts no longer read", origin);
+
+        for &other in Origin::value_variants() {
+            let diff = grid.export(&placed_words, &style(other), None, None, None).diff(&snapshot);
+            assert!(diff.changed_cells.is_empty(), "{:?} against {:?}: {:?}", other, origin, diff.changed_cells);
+            assert!(diff.moved_words.is_empty() && diff.added_words.is_empty() && diff.removed_words.is_empty(),
+                    "{:?} against {:?}", other, origin);
+        }
+    }
+}
@@ -0,0 +1,34 @@
+// A PDF whose word bank, instructions or answer key does not fit on one page carries
+// on over further pages instead of drawing below the bottom margin
+
+use wordsearch_generator::{Direction, Grid, PageSize, PlacedWord, RenderStyle};
+
+// Margin around every page, in points
+const MARGIN: f64 = 50.0;
+
+// Baseline of every piece of text drawn, from the "x y Td" operators
+fn text_baselines(pdf: &str) -> Vec<f64> {
+    pdf.split(" Td").filter_map(|before| before.split_whitespace().last()?.parse().ok()).collect()
+}
+
+#[test]
+fn long_word_bank_and_answer_key_run_onto_more_pages() {
+    let mut grid = Grid::new(3, 120);
+    let mut placed_words = Vec::new();
+    for row in 0..120 {
+        grid.place_word("CAT", row, 2, Direction::Horizontal);
+        placed_words.push(PlacedWord::new("CAT", row, 0, Direction::Horizontal));
+    }
+    let word_bank = vec!["CAT".to_string(); 300];
+    let instructions = "Find every word. ".repeat(200);
+    let style = RenderStyle { answer_key: true, ..RenderStyle::default() };
+
+    let pdf = grid.to_pdf(&placed_words, &style, Some("Cats"), Some(&instructions), &word_bank, PageSize::A4);
+    let pdf = String::from_utf8_lossy(&pdf);
+
+    let pages = pdf.matches("/Type /Page ").count();
+    assert!(pages > 3, "expected the word bank and answer key to need several pages, got {}", pages);
+    let baselines = text_baselines(&pdf);
+    assert!(!baselines.is_empty());
+    assert!(baselines.iter().all(|&y| (MARGIN..=842.0 - MARGIN).contains(&y)), "text drawn outside the margins");
+}
@@ -0,0 +1,34 @@
+// Pinned words are checked against the cells generation may use, so a pin outside the
+// shape or inside the reserved region is reported before generating, with either
+// reading order
+
+mod common;
+
+use common::{generator, word_lists};
+use wordsearch_generator::{Direction, GenerationConfig, Pin, ReadingOrder, Region, Shape};
+
+fn conflicts(pin: Pin, config: GenerationConfig) -> Vec<String> {
+    let mut words = word_lists(&["OWL"], &["BAT"]);
+    words.pinned.insert("CAT".to_string(), pin);
+    let generator = generator(words, GenerationConfig { fixed_width: Some(9), fixed_height: Some(9), ..config });
+    generator.pin_conflicts().into_iter().map(str::to_string).collect()
+}
+
+#[test]
+fn pin_outside_the_shape_conflicts() {
+    for reading_order in [ReadingOrder::Natural, ReadingOrder::Reversed] {
+        let config = || GenerationConfig { shape: Some(Shape::Circle), reading_order, ..GenerationConfig::default() };
+        let corner = Pin { row: 0, col: 0, direction: Direction::Horizontal };
+        assert_eq!(conflicts(corner, config()), ["CAT"], "{:?}", reading_order);
+        let middle = Pin { row: 4, col: 3, direction: Direction::Horizontal };
+        assert!(conflicts(middle, config()).is_empty(), "{:?}", reading_order);
+    }
+}
+
+#[test]
+fn pin_in_the_reserved_region_conflicts() {
+    let reserved = Region { top: 0, left: 0, bottom: 1, right: 8 };
+    let config = || GenerationConfig { reserved: Some(reserved), ..GenerationConfig::default() };
+    assert_eq!(conflicts(Pin { row: 1, col: 2, direction: Direction::Horizontal }, config()), ["CAT"]);
+    assert!(conflicts(Pin { row: 2, col: 2, direction: Direction::Horizontal }, config()).is_empty());
+}
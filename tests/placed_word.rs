@@ -0,0 +1,24 @@
+// A placed word's start is always its top/left-most cell, whichever way it reads, so
+// its cells in letter order and its last letter depend on both direction and reversed
+
+use wordsearch_generator::{Direction, PlacedWord};
+
+fn cat(direction: Direction, reversed: bool) -> PlacedWord {
+    PlacedWord { reversed, ..PlacedWord::new("CAT", 2, 3, direction) }
+}
+
+#[test]
+fn cells_and_end_for_each_direction_and_reading_order() {
+    let cases = [
+        (Direction::Horizontal, false, vec![(2, 3), (2, 4), (2, 5)], (2, 5)),
+        (Direction::Horizontal, true, vec![(2, 5), (2, 4), (2, 3)], (2, 3)),
+        (Direction::Vertical, false, vec![(2, 3), (3, 3), (4, 3)], (4, 3)),
+        (Direction::Vertical, true, vec![(4, 3), (3, 3), (2, 3)], (2, 3)),
+    ];
+    for (direction, reversed, cells, end) in cases {
+        let word = cat(direction, reversed);
+        assert_eq!(word.cells().collect::<Vec<_>>(), cells, "{:?}, reversed {}", direction, reversed);
+        assert_eq!(word.end_position(), end, "{:?}, reversed {}", direction, reversed);
+        assert_eq!(word.cells().last(), Some(end), "{:?}, reversed {}", direction, reversed);
+    }
+}
@@ -0,0 +1,50 @@
+// Reversed reading order lays the words out half-turned and turns the grid back at the
+// end, which must leave the shape, the reserved region and the pins where they were
+// given rather than turning them too
+
+mod common;
+
+use common::word_lists;
+use wordsearch_generator::{Direction, GenerationConfig, Pin, ReadingOrder, Region, Shape, WordLists, WordSearchGenerator};
+
+fn generator(words: WordLists, config: GenerationConfig) -> WordSearchGenerator {
+    common::generator(words, GenerationConfig { reading_order: ReadingOrder::Reversed, seed: Some(1), ..config })
+}
+
+#[test]
+fn pinned_word_keeps_its_cells() {
+    let mut words = word_lists(&["ONE", "TWO"], &["SEVEN", "EIGHT"]);
+    words.pinned.insert("CAT".to_string(), Pin { row: 1, col: 2, direction: Direction::Horizontal });
+    let generator = generator(words, GenerationConfig { fixed_width: Some(7), fixed_height: Some(7), ..GenerationConfig::default() });
+    assert!(generator.pin_conflicts().is_empty());
+
+    let (grid, placed_words) = generator.generate(50).expect("words should fit around the pin");
+    let cat = placed_words.iter().find(|word| word.word == "CAT").expect("CAT is placed");
+    assert_eq!((cat.start_row, cat.start_col, cat.direction, cat.reversed), (1, 2, Direction::Horizontal, true));
+    assert_eq!(grid.cells[1][2..5], [Some('T'), Some('A'), Some('C')]);
+    assert!(grid.misplaced_words(&placed_words).is_empty());
+    assert!(placed_words.iter().all(|word| word.reversed));
+}
+
+#[test]
+fn shape_and_reserved_region_stay_in_place() {
+    let words = word_lists(&["ONE", "TWO"], &["SIX", "TEN"]);
+    let region = Region { top: 0, left: 0, bottom: 1, right: 1 };
+    let generator = generator(words, GenerationConfig {
+        fixed_width: Some(9),
+        fixed_height: Some(9),
+        shape: Some(Shape::Heart),
+        reserved: Some(region),
+        ..GenerationConfig::default()
+    });
+
+    let (grid, placed_words) = generator.generate(50).expect("words should fit in the heart");
+    let mut expected = Shape::Heart.mask(9, 9);
+    for (row, cells) in expected.iter_mut().enumerate() {
+        for (col, open) in cells.iter_mut().enumerate() {
+            *open &= !region.contains(row, col);
+        }
+    }
+    assert_eq!(grid.mask, Some(expected));
+    assert!(placed_words.iter().flat_map(|word| word.cells()).all(|(row, col)| grid.mask.as_ref().unwrap()[row][col]));
+}
@@ -0,0 +1,39 @@
+// The statistics on the closest rejected layout explain a failed run. Annealing and
+// compaction reject candidate layouts of a run that succeeds too, so once a solution
+// is accepted they must be empty
+
+mod common;
+
+use common::word_lists;
+use wordsearch_generator::{GenerationConfig, WordSearchGenerator};
+
+fn generator(config: GenerationConfig) -> WordSearchGenerator {
+    let numbers = ["ONE", "TWO", "THREE", "FOUR", "FIVE", "SIX", "SEVEN", "EIGHT", "NINE", "TEN"];
+    common::generator(word_lists(&numbers, &numbers), config)
+}
+
+#[test]
+fn successful_max_aspect_run_reports_no_rejected_aspect() {
+    let mut successes = 0;
+    for seed in 0..10 {
+        let generator = generator(GenerationConfig { max_aspect: Some(1.0), seed: Some(seed), ..GenerationConfig::default() });
+        if generator.generate(50).is_some() {
+            successes += 1;
+            assert_eq!(generator.stats().best_rejected_aspect, None, "seed {}", seed);
+        }
+    }
+    assert!(successes > 0, "no square layout found");
+}
+
+#[test]
+fn successful_connected_run_reports_no_floating_words() {
+    let mut successes = 0;
+    for seed in 0..10 {
+        let generator = generator(GenerationConfig { all_words_connected: true, seed: Some(seed), ..GenerationConfig::default() });
+        if generator.generate(50).is_some() {
+            successes += 1;
+            assert!(generator.stats().floating_words.is_empty(), "seed {}: {:?}", seed, generator.stats().floating_words);
+        }
+    }
+    assert!(successes > 0, "no connected layout found");
+}
@@ -0,0 +1,47 @@
+// Required crossings are placed at the start of every attempt, so each algorithm,
+// including the standard one that otherwise places words at random, must meet them.
+// With flexible directions a pair from the same list can be met by turning one word
+
+mod common;
+
+use clap::ValueEnum;
+use common::word_lists;
+use wordsearch_generator::{Algorithm, GenerationConfig, WordSearchGenerator};
+
+fn generator(horizontal: &[&str], vertical: &[&str], required: &[(&str, &str)], config: GenerationConfig) -> WordSearchGenerator {
+    let mut words = word_lists(horizontal, vertical);
+    words.required_crossings = required.iter().map(|&(first, second)| (first.to_string(), second.to_string())).collect();
+    common::generator(words, config)
+}
+
+#[test]
+fn every_algorithm_meets_a_required_crossing() {
+    // ZEBRA and QUIZ share only the Z, which no algorithm favours over the other crossings
+    let horizontal = ["ZEBRA", "MONKEY", "ANTELOPE", "TIGER"];
+    let vertical = ["QUIZ", "GIRAFFE", "OTTER", "PANTHER"];
+    for &algorithm in Algorithm::value_variants() {
+        for seed in 0..5 {
+            let generator = generator(&horizontal, &vertical, &[("ZEBRA", "QUIZ")], GenerationConfig {
+                algorithm: Some(algorithm),
+                seed: Some(seed),
+                ..GenerationConfig::default()
+            });
+            let (_, placed_words) = generator.generate(20)
+                .unwrap_or_else(|| panic!("{:?} with seed {} found no layout", algorithm, seed));
+            let cells = generator.required_crossing_cells(&placed_words);
+            assert!(cells[0].2.is_some(), "{:?} with seed {} left ZEBRA apart from QUIZ", algorithm, seed);
+        }
+    }
+}
+
+#[test]
+fn flexible_directions_allow_a_same_list_pair() {
+    let config = GenerationConfig { seed: Some(1), flexible_directions: true, ..GenerationConfig::default() };
+    let generator = generator(&["ZEBRA", "QUIZ", "TIGER"], &["OTTER"], &[("ZEBRA", "QUIZ")], config);
+    assert!(generator.impossible_crossings().is_empty());
+    let (_, placed_words) = generator.generate(20).expect("words should fit");
+    assert!(generator.required_crossing_cells(&placed_words)[0].2.is_some());
+
+    let fixed = self::generator(&["ZEBRA", "QUIZ", "TIGER"], &["OTTER"], &[("ZEBRA", "QUIZ")], GenerationConfig::default());
+    assert_eq!(fixed.impossible_crossings().len(), 1);
+}
@@ -0,0 +1,24 @@
+// A small size factor shrinks the estimated grid, but never below the longest word
+// running along each side, so a word list that fits at all is still placed
+
+mod common;
+
+use clap::ValueEnum;
+use common::{generator, word_lists};
+use wordsearch_generator::{Algorithm, GenerationConfig};
+
+#[test]
+fn tiny_size_factor_still_fits_the_longest_word() {
+    let cases = [(vec!["CAT"], vec![]), (vec!["ABCDEFGHIJ"], vec!["BEE"]), (vec![], vec!["ABCDEFGHIJ"])];
+    for (horizontal, vertical) in cases {
+        for size_factor in [0.01, 0.4] {
+            for algorithm in Algorithm::value_variants().iter().copied().map(Some).chain([None]) {
+                let config = GenerationConfig { size_factor, algorithm, seed: Some(2), ..GenerationConfig::default() };
+                let generator = generator(word_lists(&horizontal, &vertical), config);
+                let (_, placed_words) = generator.generate(20)
+                    .unwrap_or_else(|| panic!("{:?} at size factor {} placed nothing for {:?}/{:?}", algorithm, size_factor, horizontal, vertical));
+                assert_eq!(placed_words.len(), horizontal.len() + vertical.len());
+            }
+        }
+    }
+}